@@ -1,6 +1,7 @@
 import { EntryRecord, ZomeClient } from '@holochain-open-dev/utils';
 import {
   ActionHash,
+  AgentPubKey,
   AppAgentClient,
   Delete,
   Link,
@@ -9,10 +10,20 @@ import {
 } from '@holochain/client';
 
 import {
+  AdminHistoryItem,
   AssembleSignal,
   Assembly,
+  BoundingBox,
   CallToAction,
+  CallToActionProgress,
+  CallToActionStatus,
+  CallToActionTree,
   Commitment,
+  CommitmentApproval,
+  CommitmentRejection,
+  DnaProperties,
+  Pagination,
+  RadiusQuery,
   Satisfaction,
 } from './types.js';
 
@@ -63,6 +74,107 @@ export class AssembleClient extends ZomeClient<AssembleSignal> {
     return new EntryRecord(record);
   }
 
+  getCallToActionStatus(
+    callToActionHash: ActionHash
+  ): Promise<CallToActionStatus> {
+    return this.callZome('get_call_to_action_status', callToActionHash);
+  }
+
+  getAllCallsToAction(pagination?: Pagination): Promise<Array<Link>> {
+    return this.callZome('get_all_calls_to_action', pagination);
+  }
+
+  getOpenCallsToAction(pagination?: Pagination): Promise<Array<Link>> {
+    return this.callZome('get_open_calls_to_action', pagination);
+  }
+
+  getClosedCallsToAction(pagination?: Pagination): Promise<Array<Link>> {
+    return this.callZome('get_closed_calls_to_action', pagination);
+  }
+
+  getCallsToActionByTag(tag: string): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_by_tag', tag);
+  }
+
+  getCallsToActionExpiringBetween(
+    start: number,
+    end: number
+  ): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_expiring_between', {
+      start,
+      end,
+    });
+  }
+
+  getCallsToActionInBoundingBox(
+    boundingBox: BoundingBox
+  ): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_in_bounding_box', boundingBox);
+  }
+
+  getCallsToActionWithinRadius(query: RadiusQuery): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_within_radius', query);
+  }
+
+  getCallToActionProgress(
+    callToActionHash: ActionHash
+  ): Promise<CallToActionProgress> {
+    return this.callZome('get_call_to_action_progress', callToActionHash);
+  }
+
+  getCallToActionSubtree(
+    callToActionHash: ActionHash
+  ): Promise<CallToActionTree> {
+    return this.callZome('get_call_to_action_subtree', callToActionHash);
+  }
+
+  getCallsToActionForAgent(agent: AgentPubKey): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_for_agent', agent);
+  }
+
+  getCallsToActionForAdmin(admin: AgentPubKey): Promise<Array<Link>> {
+    return this.callZome('get_calls_to_action_for_admin', admin);
+  }
+
+  async addAdmin(
+    callToActionHash: ActionHash,
+    admin: AgentPubKey
+  ): Promise<EntryRecord<CallToAction>> {
+    const record: Record = await this.callZome('add_admin', {
+      call_to_action_hash: callToActionHash,
+      admin,
+    });
+    return new EntryRecord(record);
+  }
+
+  async removeAdmin(
+    callToActionHash: ActionHash,
+    admin: AgentPubKey
+  ): Promise<EntryRecord<CallToAction>> {
+    const record: Record = await this.callZome('remove_admin', {
+      call_to_action_hash: callToActionHash,
+      admin,
+    });
+    return new EntryRecord(record);
+  }
+
+  async transferOwnership(
+    callToActionHash: ActionHash,
+    newOwner: AgentPubKey
+  ): Promise<EntryRecord<CallToAction>> {
+    const record: Record = await this.callZome('transfer_ownership', {
+      call_to_action_hash: callToActionHash,
+      new_owner: newOwner,
+    });
+    return new EntryRecord(record);
+  }
+
+  getAdminHistory(
+    callToActionHash: ActionHash
+  ): Promise<Array<AdminHistoryItem>> {
+    return this.callZome('get_admin_history', callToActionHash);
+  }
+
   async getCallToActionsForCallToAction(
     callToActionHash: ActionHash
   ): Promise<Array<Link>> {
@@ -98,6 +210,46 @@ export class AssembleClient extends ZomeClient<AssembleSignal> {
       callToActionHash
     );
   }
+
+  getCommitmentsForAgent(agent: AgentPubKey): Promise<Array<Link>> {
+    return this.callZome('get_commitments_for_agent', agent);
+  }
+  /** Commitment Approval */
+
+  async approveCommitment(
+    commitmentHash: ActionHash
+  ): Promise<EntryRecord<CommitmentApproval>> {
+    const record: Record = await this.callZome(
+      'approve_commitment',
+      commitmentHash
+    );
+    return new EntryRecord(record);
+  }
+
+  async rejectCommitment(
+    commitmentHash: ActionHash,
+    reason: string
+  ): Promise<EntryRecord<CommitmentRejection>> {
+    const record: Record = await this.callZome('reject_commitment', {
+      commitment_hash: commitmentHash,
+      reason,
+    });
+    return new EntryRecord(record);
+  }
+
+  getApprovalsForCommitment(commitmentHash: ActionHash): Promise<Array<Link>> {
+    return this.callZome('get_approvals_for_commitment', commitmentHash);
+  }
+
+  getRejectionsForCommitment(
+    commitmentHash: ActionHash
+  ): Promise<Array<Link>> {
+    return this.callZome('get_rejections_for_commitment', commitmentHash);
+  }
+
+  getPendingApprovalsForAdmin(admin: AgentPubKey): Promise<Array<ActionHash>> {
+    return this.callZome('get_pending_approvals_for_admin', admin);
+  }
   /** Satisfaction */
 
   async createSatisfaction(
@@ -140,16 +292,55 @@ export class AssembleClient extends ZomeClient<AssembleSignal> {
     );
   }
 
+  async getCanonicalSatisfactionsForCallToAction(
+    callToActionHash: ActionHash
+  ): Promise<Array<Link>> {
+    return this.callZome(
+      'get_canonical_satisfactions_for_call_to_action',
+      callToActionHash
+    );
+  }
+
   async getSatisfactionsForCommitment(
     commitmentHash: ActionHash
   ): Promise<Array<Link>> {
     return this.callZome('get_satisfactions_for_commitment', commitmentHash);
   }
 
+  getSatisfactionsForAgent(agent: AgentPubKey): Promise<Array<Link>> {
+    return this.callZome('get_satisfactions_for_agent', agent);
+  }
+
   deleteSatisfaction(satisfactionHash: ActionHash): Promise<ActionHash> {
     return this.callZome('delete_satisfaction', satisfactionHash);
   }
 
+  cancelCommitment(commitmentHash: ActionHash, reason: string): Promise<Record> {
+    return this.callZome('cancel_commitment', {
+      commitment_hash: commitmentHash,
+      reason,
+    });
+  }
+
+  undoCommitmentCancellation(
+    commitmentHash: ActionHash,
+    cancellationHash: ActionHash
+  ): Promise<void> {
+    return this.callZome('undo_commitment_cancellation', {
+      commitment_hash: commitmentHash,
+      cancellation_hash: cancellationHash,
+    });
+  }
+
+  reconcileSatisfactionsForCommitment(
+    commitmentHash: ActionHash
+  ): Promise<void> {
+    return this.callZome(
+      'reconcile_satisfactions_for_commitment',
+      commitmentHash
+    );
+  }
+
   getSatisfactionDeletes(
     satisfactionHash: ActionHash
   ): Promise<Array<SignedActionHashed<Delete>>> {
@@ -163,6 +354,16 @@ export class AssembleClient extends ZomeClient<AssembleSignal> {
     return new EntryRecord(record);
   }
 
+  async createCountersignedAssembly(
+    callToActionHash: ActionHash
+  ): Promise<EntryRecord<Assembly>> {
+    const record: Record = await this.callZome(
+      'create_countersigned_assembly',
+      callToActionHash
+    );
+    return new EntryRecord(record);
+  }
+
   async getAssembly(
     assemblyHash: ActionHash
   ): Promise<EntryRecord<Assembly> | undefined> {
@@ -170,15 +371,46 @@ export class AssembleClient extends ZomeClient<AssembleSignal> {
     return record ? new EntryRecord(record) : undefined;
   }
 
+  getAssemblyDeletes(
+    assemblyHash: ActionHash
+  ): Promise<Array<SignedActionHashed<Delete>>> {
+    return this.callZome('get_assembly_deletes', assemblyHash);
+  }
+
   async getAssembliesForCallToAction(
     callToActionHash: ActionHash
   ): Promise<Array<Link>> {
     return this.callZome('get_assemblies_for_call_to_action', callToActionHash);
   }
 
+  async getCanonicalAssemblyForCallToAction(
+    callToActionHash: ActionHash
+  ): Promise<Link | undefined> {
+    return this.callZome(
+      'get_canonical_assembly_for_call_to_action',
+      callToActionHash
+    );
+  }
+
   async getAssembliesForSatisfaction(
     satisfactionHash: ActionHash
   ): Promise<Array<Link>> {
     return this.callZome('get_assemblies_for_satisfaction', satisfactionHash);
   }
+
+  getAssembliesForAgent(agent: AgentPubKey): Promise<Array<Link>> {
+    return this.callZome('get_assemblies_for_agent', agent);
+  }
+
+  /** DNA Properties */
+
+  getDnaProperties(): Promise<DnaProperties> {
+    return this.callZome('get_dna_properties', null);
+  }
+
+  /** Membrane */
+
+  createInvitation(invitee: AgentPubKey): Promise<Uint8Array> {
+    return this.callZome('create_invitation', invitee);
+  }
 }
@@ -1,16 +1,20 @@
-import { CancellationsStore } from '@holochain-open-dev/cancellations';
+import {
+  CancellationsClient,
+  CancellationsStore,
+} from '@holochain-open-dev/cancellations';
+import { ProfilesClient, ProfilesStore } from '@holochain-open-dev/profiles';
 import {
   deletesForEntryStore,
   immutableEntryStore,
   joinAsync,
   latestVersionOfEntryStore,
+  lazyLoadAndPoll,
   liveLinksStore,
   mapAndJoin,
   pipe,
-  toPromise,
 } from '@holochain-open-dev/stores';
 import { LazyHoloHashMap, slice } from '@holochain-open-dev/utils';
-import { ActionHash } from '@holochain/client';
+import { ActionHash, AgentPubKey, AppAgentClient } from '@holochain/client';
 
 import { AssembleClient } from './assemble-client.js';
 import { Need } from './types.js';
@@ -21,175 +25,20 @@ export class AssembleStore {
     public cancellationsStore: CancellationsStore
   ) {
     cancellationsStore.client.onSignal(async signal => {
+      let cancelledHash: ActionHash | undefined;
       if (signal.type === 'LinkDeleted') {
         // Something was uncancelled
-        try {
-          const commitmentHash = signal.action.hashed.content.base_address;
-          const commitment = await toPromise(
-            this.commitments.get(commitmentHash).entry
-          );
-
-          // TODO: better check on whether what was cancelled was a commitment
-          if (!commitment.entry.amount) return;
-
-          const callToActionHash = commitment.entry.call_to_action_hash;
-          const callToAction = await toPromise(
-            this.callToActions.get(callToActionHash).latestVersion
-          );
-
-          const need = callToAction.entry.needs[commitment.entry.need_index];
-          if (!need.requires_admin_approval) {
-            // Create a new satisfaction if there are already enough commitments
-            let commitmentHashes = Array.from(
-              (
-                await toPromise(
-                  this.callToActions.get(callToActionHash).commitments
-                    .uncancelled
-                )
-              ).keys()
-            );
-            commitmentHashes = [
-              ...commitmentHashes.filter(
-                h => h.toString() !== commitmentHash.toString()
-              ),
-              commitmentHash,
-            ];
-            const commitments = await toPromise(
-              mapAndJoin(
-                slice(this.commitments, commitmentHashes),
-                c => c.entry
-              )
-            );
-
-            const amountContributed = Array.from(commitments.values()).reduce(
-              (acc, next) => acc + next.entry.amount,
-              0
-            );
-
-            if (amountContributed >= need.min_necessary) {
-              const satisfactionsForCallToAction = await toPromise(
-                mapAndJoin(
-                  slice(
-                    this.satisfactions,
-                    Array.from(
-                      (
-                        await toPromise(
-                          this.callToActions.get(callToActionHash).satisfactions
-                        )
-                      ).keys()
-                    )
-                  ),
-                  s => s.latestVersion
-                )
-              );
-
-              if (
-                !Array.from(satisfactionsForCallToAction.values()).find(
-                  satisfaction =>
-                    satisfaction.entry.need_index ===
-                    commitment.entry.need_index
-                )
-              ) {
-                await this.client.createSatisfaction({
-                  need_index: commitment.entry.need_index,
-                  call_to_action_hash: callToActionHash,
-                  commitments_hashes: commitmentHashes,
-                });
-              }
-            }
-          }
-        } catch (e) {
-          // If this is not a commitment, nothing to do
-          console.warn(e);
-        }
+        cancelledHash = signal.action.hashed.content.base_address;
       } else if (signal.type === 'EntryCreated') {
-        // If a commitment was cancelled, delete any satisfactions for it
-        try {
-          const commitmentHash = signal.app_entry.cancelled_hash;
-          const commitment = await toPromise(
-            this.commitments.get(commitmentHash).entry
-          );
-
-          // TODO: better check on whether what was cancelled was a commitment
-          if (!commitment.entry.amount) return;
-          const callToActionHash = commitment.entry.call_to_action_hash;
-          const callToAction = await toPromise(
-            this.callToActions.get(callToActionHash).latestVersion
-          );
-
-          const need = callToAction.entry.needs[commitment.entry.need_index];
-          if (need.requires_admin_approval) {
-            // If the admins need to approve this need, delete satisfactions
-            const satisfactionsForCommitment = Array.from(
-              (
-                await toPromise(
-                  this.commitments.get(commitmentHash).satisfactions
-                )
-              ).keys()
-            );
-
-            for (const satisfactionHash of satisfactionsForCommitment) {
-              await this.client.deleteSatisfaction(satisfactionHash); // TODO: HEEEEERE
-            }
-          } else {
-            // Only delete the satisfaction if there are not enough commitments to satisfy the need
-            let commitmentHashes = Array.from(
-              (
-                await toPromise(
-                  this.callToActions.get(callToActionHash).commitments
-                    .uncancelled
-                )
-              ).keys()
-            );
-            commitmentHashes = commitmentHashes.filter(
-              h => h.toString() !== commitmentHash.toString()
-            );
-            const commitments = await toPromise(
-              mapAndJoin(
-                slice(this.commitments, commitmentHashes),
-                c => c.entry
-              )
-            );
-            const commitmentsForNeed = Array.from(commitments.values()).filter(
-              c => c.entry.need_index === commitment.entry.need_index
-            );
-            const amountContributed = commitmentsForNeed.reduce(
-              (acc, next) => acc + next.entry.amount,
-              0
-            );
-
-            if (amountContributed < need.min_necessary) {
-              const satisfactionsForCallToAction = await toPromise(
-                mapAndJoin(
-                  slice(
-                    this.satisfactions,
-                    Array.from(
-                      (
-                        await toPromise(
-                          this.callToActions.get(callToActionHash).satisfactions
-                        )
-                      ).keys()
-                    )
-                  ),
-                  s => s.latestVersion
-                )
-              );
+        // Something was cancelled
+        cancelledHash = signal.app_entry.cancelled_hash;
+      }
+      if (!cancelledHash) return;
 
-              for (const satisfaction of Array.from(
-                satisfactionsForCallToAction.values()
-              )) {
-                if (
-                  satisfaction.entry.need_index === commitment.entry.need_index
-                ) {
-                  await this.client.deleteSatisfaction(satisfaction.actionHash);
-                }
-              }
-            }
-          }
-        } catch (e) {
-          // If this is not a commitment, nothing to do
-          console.warn(e);
-        }
+      try {
+        await this.client.reconcileSatisfactionsForCommitment(cancelledHash);
+      } catch (e) {
+        console.warn(e);
       }
     });
   }
@@ -215,7 +64,8 @@ export class AssembleStore {
     const satisfactionsHashes = liveLinksStore(
       this.client,
       callToActionHash,
-      () => this.client.getSatisfactionsForCallToAction(callToActionHash),
+      () =>
+        this.client.getCanonicalSatisfactionsForCallToAction(callToActionHash),
       'CallToActionToSatisfactions'
     );
     const latestVersion = latestVersionOfEntryStore(this.client, () =>
@@ -235,6 +85,10 @@ export class AssembleStore {
     ]);
     return {
       latestVersion,
+      progress: lazyLoadAndPoll(
+        () => this.client.getCallToActionProgress(callToActionHash),
+        4000
+      ),
       needs: {
         satisfied: pipe(needs, ([callToAction, satisfactions]) =>
           callToAction.entry.needs
@@ -287,7 +141,13 @@ export class AssembleStore {
         liveLinksStore(
           this.client,
           callToActionHash,
-          () => this.client.getAssembliesForCallToAction(callToActionHash),
+          async () => {
+            const link =
+              await this.client.getCanonicalAssemblyForCallToAction(
+                callToActionHash
+              );
+            return link ? [link] : [];
+          },
           'CallToActionToAssemblies'
         ),
         links =>
@@ -362,4 +222,88 @@ export class AssembleStore {
   assemblies = new LazyHoloHashMap((assemblyHash: ActionHash) =>
     immutableEntryStore(() => this.client.getAssembly(assemblyHash))
   );
+
+  /** Agent */
+
+  agents = new LazyHoloHashMap((agent: AgentPubKey) => ({
+    callsToAction: pipe(
+      liveLinksStore(
+        this.client,
+        agent,
+        () => this.client.getCallsToActionForAgent(agent),
+        'AgentToCallsToAction'
+      ),
+      links =>
+        slice(
+          this.callToActions,
+          links.map(l => l.target)
+        )
+    ),
+    commitments: pipe(
+      liveLinksStore(
+        this.client,
+        agent,
+        () => this.client.getCommitmentsForAgent(agent),
+        'AgentToCommitments'
+      ),
+      links =>
+        slice(
+          this.commitments,
+          links.map(l => l.target)
+        )
+    ),
+    satisfactions: pipe(
+      liveLinksStore(
+        this.client,
+        agent,
+        () => this.client.getSatisfactionsForAgent(agent),
+        'AgentToSatisfactions'
+      ),
+      links =>
+        slice(
+          this.satisfactions,
+          links.map(l => l.target)
+        )
+    ),
+    assemblies: pipe(
+      liveLinksStore(
+        this.client,
+        agent,
+        () => this.client.getAssembliesForAgent(agent),
+        'AgentToAssemblies'
+      ),
+      links =>
+        slice(
+          this.assemblies,
+          links.map(l => l.target)
+        )
+    ),
+  }));
+}
+
+/**
+ * Creates the assemble store along with the stores for the cancellations and profiles zomes,
+ * using the zome names set in the properties of the DNA
+ */
+export async function createAssembleStores(
+  client: AppAgentClient,
+  roleName: string,
+  zomeName = 'assemble'
+): Promise<{ assembleStore: AssembleStore; profilesStore: ProfilesStore }> {
+  const assembleClient = new AssembleClient(client, roleName, zomeName);
+  const properties = await assembleClient.getDnaProperties();
+  const cancellationsStore = new CancellationsStore(
+    new CancellationsClient(
+      client,
+      roleName,
+      properties.cancellations_zome_name
+    )
+  );
+  const profilesStore = new ProfilesStore(
+    new ProfilesClient(client, roleName, properties.profiles_zome_name)
+  );
+  return {
+    assembleStore: new AssembleStore(assembleClient, cancellationsStore),
+    profilesStore,
+  };
 }
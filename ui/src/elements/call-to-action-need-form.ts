@@ -82,6 +82,9 @@ export class CallToActionNeedForm extends LitElement implements FormField {
       requires_admin_approval: this.hideRequiresAdminApproval
         ? false
         : fields.requires_admin_approval === 'on',
+      approval_threshold: fields.approval_threshold
+        ? parseInt(fields.approval_threshold as string, 10)
+        : this.defaultValue?.approval_threshold || 1,
     };
 
     return JSON.stringify(need);
@@ -191,13 +194,24 @@ export class CallToActionNeedForm extends LitElement implements FormField {
         ${this.hideRequiresAdminApproval
           ? html``
           : html`
-              <sl-switch
-                .disabled=${!this._minRequired}
-                .defaultChecked=${false}
-                style="flex: 1; margin-top: 8px"
-                name="requires_admin_approval"
-                >${this.requiresAdminApprovalLabel}</sl-switch
-              >
+              <div class="row" style="align-items: center; gap: 8px">
+                <sl-switch
+                  .disabled=${!this._minRequired}
+                  .defaultChecked=${false}
+                  style="flex: 1; margin-top: 8px"
+                  name="requires_admin_approval"
+                  >${this.requiresAdminApprovalLabel}</sl-switch
+                >
+                <sl-input
+                  name="approval_threshold"
+                  type="number"
+                  min="1"
+                  .label=${msg('Approvals needed')}
+                  .defaultValue=${this.defaultValue?.approval_threshold || 1}
+                  .disabled=${!this._minRequired}
+                  style="width: 5rem;"
+                ></sl-input>
+              </div>
             `}
       </form>
     `;
@@ -1,11 +1,6 @@
 import { hashProperty, sharedStyles } from '@holochain-open-dev/elements';
 import '@holochain-open-dev/elements/dist/elements/display-error.js';
-import {
-  StoreSubscriber,
-  joinAsync,
-  mapAndJoin,
-  pipe,
-} from '@holochain-open-dev/stores';
+import { StoreSubscriber, joinAsync } from '@holochain-open-dev/stores';
 import { EntryRecord } from '@holochain-open-dev/utils';
 import { ActionHash } from '@holochain/client';
 import { consume } from '@lit/context';
@@ -22,7 +17,7 @@ import { customElement, property } from 'lit/decorators.js';
 
 import { AssembleStore } from '../assemble-store.js';
 import { assembleStoreContext } from '../context.js';
-import { CallToAction, Commitment, Need, Satisfaction } from '../types.js';
+import { CallToAction, CallToActionProgress, Need } from '../types.js';
 
 /**
  * @element call-to-action-need-progress
@@ -53,18 +48,7 @@ export class CallToActionNeedProgress extends LitElement {
       joinAsync([
         this.assembleStore.callToActions.get(this.callToActionHash)
           .latestVersion,
-        pipe(
-          this.assembleStore.callToActions.get(this.callToActionHash)
-            .commitments.uncancelled,
-          uncancelledCommitments =>
-            mapAndJoin(uncancelledCommitments, c => c.entry)
-        ),
-        pipe(
-          this.assembleStore.callToActions.get(this.callToActionHash)
-            .satisfactions,
-          uncancelledCommitments =>
-            mapAndJoin(uncancelledCommitments, c => c.latestVersion)
-        ),
+        this.assembleStore.callToActions.get(this.callToActionHash).progress,
       ]),
     () => [this.callToActionHash, this.needIndex]
   );
@@ -79,14 +63,10 @@ export class CallToActionNeedProgress extends LitElement {
 
   renderNeedProgress(
     callToAction: EntryRecord<CallToAction>,
-    commitments: Array<EntryRecord<Commitment>>,
-    satisfactions: Array<EntryRecord<Satisfaction>>
+    progress: CallToActionProgress
   ) {
     const need: Need = callToAction.entry.needs[this.needIndex];
-
-    const satisfied = !!satisfactions.find(
-      s => s.entry.need_index === this.needIndex
-    );
+    const needProgress = progress.needs[this.needIndex];
 
     if (need.min_necessary === 1 && need.max_possible === 1)
       return html`<div class="row" style="flex: 1">
@@ -97,23 +77,20 @@ export class CallToActionNeedProgress extends LitElement {
         <span style="flex: 1"></span><span>${msg('No min. or max.')}</span>
       </div>`;
 
-    const amountContributed = commitments
-      .filter(p => p.entry.need_index === this.needIndex)
-      .reduce((count, p) => count + p.entry.amount, 0);
+    const amountContributed = needProgress
+      ? needProgress.amount_contributed
+      : 0;
     return html`
       <div class="row" style="flex: 1; position: relative">
         <sl-progress-bar
-          style="flex: 1; --indicator-color: ${satisfied ||
-          need.min_necessary === 0 ||
+          style="flex: 1; --indicator-color: ${needProgress?.satisfied ||
           amountContributed >= need.min_necessary
             ? 'green'
             : 'var(--sl-color-primary-700)'}"
           .value=${(100 * amountContributed) /
           (need.max_possible ? need.max_possible : need.min_necessary)}
         >
-          ${commitments
-            .filter(p => p.entry.need_index === this.needIndex)
-            .reduce((count, p) => count + p.entry.amount, 0)}
+          ${amountContributed}
         </sl-progress-bar>
 
         ${need.min_necessary !== need.max_possible
@@ -166,23 +143,14 @@ export class CallToActionNeedProgress extends LitElement {
         return html` <sl-skeleton></sl-skeleton> `;
       case 'complete':
         const callToAction = this._callToActionInfo.value.value[0];
-        const commitments = Array.from(
-          this._callToActionInfo.value.value[1].values()
-        );
-        const satisfactions = Array.from(
-          this._callToActionInfo.value.value[2].values()
-        );
+        const progress = this._callToActionInfo.value.value[1];
 
         if (!callToAction)
           return html`<span
             >${msg('The requested call to action was not found.')}</span
           >`;
 
-        return this.renderNeedProgress(
-          callToAction,
-          commitments,
-          satisfactions
-        );
+        return this.renderNeedProgress(callToAction, progress);
       case 'error':
         return html`<display-error
           tooltip
@@ -44,6 +44,7 @@ export class CallToActionNeedsForm extends LitElement {
       min_necessary: 0,
       max_possible: undefined,
       requires_admin_approval: false,
+      approval_threshold: 1,
     },
   ];
 
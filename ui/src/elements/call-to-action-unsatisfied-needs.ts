@@ -105,6 +105,7 @@ export class CallToActionUnsatisfiedNeeds extends LitElement {
       const assembly = await this.assembleStore.client.createAssembly({
         call_to_action_hash: this.callToActionHash,
         satisfactions_hashes,
+        children_assemblies_hashes: [],
       });
       this.dispatchEvent(
         new CustomEvent('assembly-created', {
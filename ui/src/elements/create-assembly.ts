@@ -78,6 +78,7 @@ export class CreateAssembly extends LitElement {
     const assembly: Assembly = {
       call_to_action_hash: this.callToActionHash,
       satisfactions_hashes: this.satisfactionsHashes,
+      children_assemblies_hashes: [],
     };
 
     try {
@@ -5,7 +5,11 @@ import {
 } from '@holochain-open-dev/elements';
 import '@holochain-open-dev/elements/dist/elements/display-error.js';
 import { EntryRecord } from '@holochain-open-dev/utils';
-import { decodeHashFromBase64, encodeHashToBase64 } from '@holochain/client';
+import {
+  ActionHash,
+  decodeHashFromBase64,
+  encodeHashToBase64,
+} from '@holochain/client';
 import { consume } from '@lit/context';
 import { localized, msg } from '@lit/localize';
 import { SlDialog } from '@shoelace-style/shoelace';
@@ -89,14 +93,47 @@ export class CreateSatisfaction extends LitElement {
       .filter(([_key, value]) => value === 'on')
       .map(([key, _value]) => decodeHashFromBase64(key));
 
-    const satisfaction: Satisfaction = {
-      call_to_action_hash: this.callToAction.actionHash,
-      need_index: this.needIndex,
-      commitments_hashes,
-    };
-
     try {
       this.committing = true;
+
+      // Each commitment needs enough admin approvals to satisfy the need
+      const myPubKey = this.assembleStore.client.client.myPubKey.toString();
+      const approvalThreshold =
+        this.callToAction.entry.needs[this.needIndex].approval_threshold;
+      const approvals_hashes: Array<ActionHash> = [];
+      let allApproved = true;
+      for (const commitmentHash of commitments_hashes) {
+        let approvals =
+          await this.assembleStore.client.getApprovalsForCommitment(
+            commitmentHash
+          );
+        if (!approvals.find(l => l.author.toString() === myPubKey)) {
+          await this.assembleStore.client.approveCommitment(commitmentHash);
+          approvals =
+            await this.assembleStore.client.getApprovalsForCommitment(
+              commitmentHash
+            );
+        }
+        const approvers = new Set(approvals.map(l => l.author.toString()));
+        if (approvers.size < approvalThreshold) allApproved = false;
+        approvals_hashes.push(...approvals.map(l => l.target));
+      }
+
+      if (!allApproved) {
+        // The satisfaction will be created when the other admins approve
+        this.form.reset();
+        this.dialog.hide();
+        this.commitments = undefined;
+        this.committing = false;
+        return;
+      }
+
+      const satisfaction: Satisfaction = {
+        call_to_action_hash: this.callToAction.actionHash,
+        need_index: this.needIndex,
+        commitments_hashes,
+        approvals_hashes,
+      };
       const record: EntryRecord<Satisfaction> =
         await this.assembleStore.client.createSatisfaction(satisfaction);
 
@@ -65,6 +65,12 @@ export class EditCallToAction extends LitElement {
       needs: (Array.isArray(fields.needs) ? fields.needs : [fields.needs]).map(
         (el: any) => el
       ),
+      tags: this.currentRecord.entry.tags,
+      location: this.currentRecord.entry.location,
+      children_policy: this.currentRecord.entry.children_policy,
+      fulfillment_policy: this.currentRecord.entry.fulfillment_policy,
+      countersigned_assembly: this.currentRecord.entry.countersigned_assembly,
+      owner: this.currentRecord.entry.owner,
     };
 
     try {
@@ -56,6 +56,9 @@ export class EditSatisfaction extends LitElement {
       call_to_action_hash: this.currentRecord.entry.call_to_action_hash,
       need_index: this.currentRecord.entry.need_index,
       commitments_hashes: this.currentRecord.entry.commitments_hashes,
+      approvals_hashes: this.currentRecord.entry.approvals_hashes,
+      call_to_action_version_hash:
+        this.currentRecord.entry.call_to_action_version_hash,
     };
 
     try {
@@ -50,15 +50,20 @@ export class MyCommitmentsForCallToAction extends LitElement {
     this,
     () =>
       pipe(
-        this.assembleStore.callToActions.get(this.callToActionHash).commitments
-          .uncancelled,
-        commitments => mapAndJoin(commitments, c => c.entry),
+        this.assembleStore.agents.get(
+          this.assembleStore.client.client.myPubKey
+        ).commitments,
         commitments =>
-          Array.from(commitments.values()).filter(
-            c =>
-              c.action.author.toString() ===
-              this.assembleStore.client.client.myPubKey.toString()
-          ),
+          mapAndJoin(commitments, c => joinAsync([c.entry, c.isCancelled])),
+        commitments =>
+          Array.from(commitments.values())
+            .filter(
+              ([c, isCancelled]) =>
+                !isCancelled &&
+                c.entry.call_to_action_hash.toString() ===
+                  this.callToActionHash.toString()
+            )
+            .map(([c]) => c),
         myCommitments =>
           joinAsync(
             myCommitments.map(c =>
@@ -317,8 +317,15 @@ export async function sampleCallToAction(
         min_necessary: 1,
         max_possible: 5,
         requires_admin_approval: false,
+        approval_threshold: 1,
       },
     ],
+    tags: [],
+    location: undefined,
+    children_policy: { type: 'Independent' },
+    fulfillment_policy: { type: 'AllRequiredNeeds' },
+    countersigned_assembly: false,
+    owner: undefined,
     ...partialCallToAction,
   };
 }
@@ -369,6 +376,7 @@ export async function sampleSatisfaction(
 
   return {
     need_index: 0,
+    approvals_hashes: [],
     ...partialSatisfaction,
   } as Satisfaction;
 }
@@ -396,5 +404,8 @@ export async function sampleAssembly(
       ).actionHash,
     ];
   }
+  if (!partialAssembly.children_assemblies_hashes) {
+    partialAssembly.children_assemblies_hashes = [];
+  }
   return partialAssembly as Assembly;
 }
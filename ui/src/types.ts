@@ -1,9 +1,17 @@
 import { ActionCommittedSignal } from '@holochain-open-dev/utils';
 import { ActionHash, AgentPubKey } from '@holochain/client';
 
-export type AssembleSignal = ActionCommittedSignal<EntryTypes, any>;
+export type AssembleSignal =
+  | ActionCommittedSignal<EntryTypes, any>
+  | {
+      type: 'CommitmentRejected';
+      commitment_rejection_hash: ActionHash;
+      commitment_rejection: CommitmentRejection;
+    };
 
 export type EntryTypes =
+  | ({ type: 'CommitmentRejection' } & CommitmentRejection)
+  | ({ type: 'CommitmentApproval' } & CommitmentApproval)
   | ({ type: 'Assembly' } & Assembly)
   | ({ type: 'Satisfaction' } & Satisfaction)
   | ({ type: 'Commitment' } & Commitment)
@@ -14,6 +22,99 @@ export interface CallToAction {
   parent_call_to_action_hash: ActionHash | undefined;
   expiration_time: number | undefined;
   needs: Array<Need>;
+  tags: Array<string>;
+  location: Location | undefined;
+  children_policy: ChildrenPolicy;
+  fulfillment_policy: FulfillmentPolicy;
+  countersigned_assembly: boolean;
+  owner: AgentPubKey | undefined;
+}
+
+export type ChildrenPolicy =
+  | { type: 'Independent' }
+  | { type: 'AllChildren' }
+  | { type: 'SomeChildren'; children_hashes: Array<ActionHash> };
+
+export type FulfillmentPolicy =
+  | { type: 'AllRequiredNeeds' }
+  | { type: 'AnyNeeds'; count: number }
+  | { type: 'AllRequiredAndSomeOptionalNeeds'; optional_count: number }
+  | { type: 'WeightedThreshold'; weights: Array<number>; threshold: number };
+
+export type AdminChange =
+  | { type: 'AdminAdded'; admin: AgentPubKey }
+  | { type: 'AdminRemoved'; admin: AgentPubKey }
+  | {
+      type: 'OwnershipTransferred';
+      previous_owner: AgentPubKey;
+      new_owner: AgentPubKey;
+    };
+
+export interface AdminHistoryItem {
+  call_to_action_update_hash: ActionHash;
+  author: AgentPubKey;
+  timestamp: number;
+  changes: Array<AdminChange>;
+}
+
+export interface DnaProperties {
+  progenitor: string | undefined;
+  max_needs: number;
+  max_description_length: number;
+  max_expiration_horizon_seconds: number | undefined;
+  anyone_can_create_calls_to_action: boolean;
+  cancellations_zome_name: string;
+  profiles_zome_name: string;
+}
+
+export interface CallToActionTree {
+  call_to_action_hash: ActionHash;
+  status: CallToActionStatus;
+  children: Array<CallToActionTree>;
+}
+
+export interface Location {
+  latitude: number;
+  longitude: number;
+  place_name: string;
+}
+
+export interface BoundingBox {
+  min_latitude: number;
+  min_longitude: number;
+  max_latitude: number;
+  max_longitude: number;
+}
+
+export interface RadiusQuery {
+  latitude: number;
+  longitude: number;
+  radius_km: number;
+}
+
+export type CallToActionStatus =
+  | { type: 'Open' }
+  | { type: 'Expired' }
+  | { type: 'Fulfilled' }
+  | { type: 'Cancelled' };
+
+export interface Pagination {
+  offset: number;
+  limit: number;
+}
+
+export interface NeedProgress {
+  need_index: number;
+  amount_contributed: number;
+  committers_count: number;
+  satisfied: boolean;
+  satisfaction_hash: ActionHash | undefined;
+}
+
+export interface CallToActionProgress {
+  needs: Array<NeedProgress>;
+  assembled: boolean;
+  assembly_hash: ActionHash | undefined;
 }
 
 export interface Need {
@@ -21,6 +122,7 @@ export interface Need {
   max_possible: number | undefined;
   description: string;
   requires_admin_approval: boolean;
+  approval_threshold: number;
 }
 
 export interface Commitment {
@@ -30,6 +132,8 @@ export interface Commitment {
 
   comment: string;
   amount: number;
+
+  call_to_action_version_hash?: ActionHash;
 }
 
 export interface Satisfaction {
@@ -38,10 +142,28 @@ export interface Satisfaction {
   need_index: number;
 
   commitments_hashes: Array<ActionHash>;
+
+  approvals_hashes: Array<ActionHash>;
+
+  call_to_action_version_hash?: ActionHash;
 }
 
 export interface Assembly {
   call_to_action_hash: ActionHash;
 
   satisfactions_hashes: Array<ActionHash>;
+
+  children_assemblies_hashes: Array<ActionHash>;
+
+  call_to_action_version_hash?: ActionHash;
+}
+
+export interface CommitmentApproval {
+  commitment_hash: ActionHash;
+}
+
+export interface CommitmentRejection {
+  commitment_hash: ActionHash;
+
+  reason: string;
 }
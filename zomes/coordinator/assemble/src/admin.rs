@@ -0,0 +1,159 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+use crate::call_to_action::{
+    get_latest_call_to_action, update_call_to_action, UpdateCallToActionInput,
+};
+use crate::error::AssembleError;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangeAdminInput {
+    pub call_to_action_hash: ActionHash,
+    pub admin: AgentPubKey,
+}
+
+#[hdk_extern]
+pub fn add_admin(input: ChangeAdminInput) -> ExternResult<Record> {
+    update_admins(input.call_to_action_hash, |owner, call_to_action| {
+        if owner.eq(&input.admin) || call_to_action.admins.contains(&input.admin) {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "This agent already owns or administers the call to action"
+            ))));
+        }
+        call_to_action.admins.push(input.admin);
+        Ok(())
+    })
+}
+
+#[hdk_extern]
+pub fn remove_admin(input: ChangeAdminInput) -> ExternResult<Record> {
+    update_admins(input.call_to_action_hash, |_owner, call_to_action| {
+        if !call_to_action.admins.contains(&input.admin) {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "This agent is not an admin of the call to action"
+            ))));
+        }
+        call_to_action.admins.retain(|admin| admin.ne(&input.admin));
+        if call_to_action.admins.is_empty() {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "The last admin of a call to action can't be removed"
+            ))));
+        }
+        Ok(())
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransferOwnershipInput {
+    pub call_to_action_hash: ActionHash,
+    pub new_owner: AgentPubKey,
+}
+
+/// Makes the new owner the owner of the call to action, and keeps the previous owner as one of its admins
+#[hdk_extern]
+pub fn transfer_ownership(input: TransferOwnershipInput) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    update_admins(input.call_to_action_hash, |owner, call_to_action| {
+        if owner.ne(&my_pub_key) {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Only the owner of a call to action can transfer its ownership"
+            ))));
+        }
+        if owner.eq(&input.new_owner) {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "This agent already owns the call to action"
+            ))));
+        }
+        call_to_action
+            .admins
+            .retain(|admin| admin.ne(&input.new_owner));
+        call_to_action.admins.push(owner.clone());
+        call_to_action.owner = Some(input.new_owner);
+        Ok(())
+    })
+}
+
+/// Updates the latest version of the call to action with the given change of its owner or admins
+fn update_admins(
+    call_to_action_hash: ActionHash,
+    change: impl FnOnce(&AgentPubKey, &mut CallToAction) -> ExternResult<()>,
+) -> ExternResult<Record> {
+    let original_record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let author = original_record.action().author().clone();
+    let latest_record = get_latest_call_to_action(call_to_action_hash.clone())?
+        .ok_or(AssembleError::CallToActionNotFound(call_to_action_hash))?;
+    let previous_call_to_action_hash = latest_record.action_address().clone();
+    let mut updated_call_to_action = CallToAction::try_from(latest_record)?;
+
+    let owner = get_call_to_action_owner(&author, &updated_call_to_action);
+    change(&owner, &mut updated_call_to_action)?;
+
+    update_call_to_action(UpdateCallToActionInput {
+        previous_call_to_action_hash,
+        updated_call_to_action,
+    })
+}
+
+/// Records the changes in the owner and the admins of the update in the admin history of the call to action
+pub fn create_admin_changes_link(
+    original_call_to_action_hash: ActionHash,
+    updated_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    let original_record = get(original_call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+        AssembleError::CallToActionNotFound(original_call_to_action_hash.clone()),
+    )?;
+    let changes = get_admin_changes(
+        original_record.action().author(),
+        previous_call_to_action,
+        updated_call_to_action,
+    );
+    if changes.is_empty() {
+        return Ok(());
+    }
+    create_link(
+        original_call_to_action_hash,
+        updated_call_to_action_hash,
+        LinkTypes::CallToActionToAdminChanges,
+        LinkTag::try_from(AdminChangesLinkTag { changes })?,
+    )?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AdminHistoryItem {
+    pub call_to_action_update_hash: ActionHash,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub changes: Vec<AdminChange>,
+}
+
+/// Returns every change in the owner and the admins of the call to action, oldest first
+#[hdk_extern]
+pub fn get_admin_history(call_to_action_hash: ActionHash) -> ExternResult<Vec<AdminHistoryItem>> {
+    let links = get_links(
+        call_to_action_hash,
+        LinkTypes::CallToActionToAdminChanges,
+        None,
+    )?;
+    let mut history: Vec<AdminHistoryItem> = vec![];
+    for link in links {
+        let Some(call_to_action_update_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Ok(tag) = AdminChangesLinkTag::try_from(link.tag) else {
+            continue;
+        };
+        history.push(AdminHistoryItem {
+            call_to_action_update_hash,
+            author: link.author,
+            timestamp: link.timestamp,
+            changes: tag.changes,
+        });
+    }
+    history.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(history)
+}
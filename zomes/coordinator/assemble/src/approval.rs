@@ -0,0 +1,214 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::call_to_action::{
+    get_call_to_action_status, get_calls_to_action_for_admin, get_calls_to_action_for_agent,
+    get_latest_call_to_action, CallToActionStatus,
+};
+use crate::commitment::{get_commitment, get_uncancelled_commitments_for_need};
+use crate::error::AssembleError;
+use crate::Signal;
+
+#[hdk_extern]
+pub fn approve_commitment(commitment_hash: ActionHash) -> ExternResult<Record> {
+    if is_commitment_rejected(commitment_hash.clone())? {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Can't approve a commitment that has already been rejected"
+        ))));
+    }
+    if get_commitment_approvals(commitment_hash.clone())?
+        .contains_key(&agent_info()?.agent_latest_pubkey)
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "You have already approved this commitment"
+        ))));
+    }
+
+    let commitment_approval = CommitmentApproval {
+        commitment_hash: commitment_hash.clone(),
+    };
+    let commitment_approval_hash =
+        create_entry(&EntryTypes::CommitmentApproval(commitment_approval.clone()))?;
+    create_link(
+        commitment_hash,
+        commitment_approval_hash.clone(),
+        LinkTypes::CommitmentToApprovals,
+        (),
+    )?;
+
+    let record = get(commitment_approval_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from(
+            "Could not find the newly created CommitmentApproval"
+        ))
+    ))?;
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RejectCommitmentInput {
+    pub commitment_hash: ActionHash,
+    pub reason: String,
+}
+
+/// Rejects the commitment and lets the committer know why with a remote signal
+#[hdk_extern]
+pub fn reject_commitment(input: RejectCommitmentInput) -> ExternResult<Record> {
+    let commitment_record = get_commitment(input.commitment_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the Commitment"))
+    ))?;
+    let committer = commitment_record.action().author().clone();
+    let commitment = Commitment::try_from(commitment_record)?;
+    if is_commitment_approved(
+        input.commitment_hash.clone(),
+        get_approval_threshold(&commitment)?,
+    )? {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Can't reject a commitment that has already been approved"
+        ))));
+    }
+
+    let commitment_rejection = CommitmentRejection {
+        commitment_hash: input.commitment_hash.clone(),
+        reason: input.reason,
+    };
+    let commitment_rejection_hash = create_entry(&EntryTypes::CommitmentRejection(
+        commitment_rejection.clone(),
+    ))?;
+    create_link(
+        input.commitment_hash,
+        commitment_rejection_hash.clone(),
+        LinkTypes::CommitmentToRejections,
+        (),
+    )?;
+
+    // The committer might be offline, in which case they will find the rejection when they query it
+    if let Err(err) = send_remote_signal(
+        Signal::CommitmentRejected {
+            commitment_rejection_hash: commitment_rejection_hash.clone(),
+            commitment_rejection,
+        },
+        vec![committer],
+    ) {
+        warn!("Could not signal the rejection to the committer: {:?}", err);
+    }
+
+    let record = get(commitment_rejection_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from(
+            "Could not find the newly created CommitmentRejection"
+        ))
+    ))?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn get_approvals_for_commitment(commitment_hash: ActionHash) -> ExternResult<Vec<Link>> {
+    get_links(commitment_hash, LinkTypes::CommitmentToApprovals, None)
+}
+
+#[hdk_extern]
+pub fn get_rejections_for_commitment(commitment_hash: ActionHash) -> ExternResult<Vec<Link>> {
+    get_links(commitment_hash, LinkTypes::CommitmentToRejections, None)
+}
+
+/// Returns the approval of each admin that approved the commitment
+pub fn get_commitment_approvals(
+    commitment_hash: ActionHash,
+) -> ExternResult<BTreeMap<AgentPubKey, ActionHash>> {
+    let mut approvals: BTreeMap<AgentPubKey, ActionHash> = BTreeMap::new();
+    for link in get_approvals_for_commitment(commitment_hash)? {
+        let Some(approval_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        // The link author is validated to be the author of the approval
+        match approvals.get(&link.author) {
+            Some(existing_hash) if existing_hash <= &approval_hash => {}
+            _ => {
+                approvals.insert(link.author, approval_hash);
+            }
+        }
+    }
+    Ok(approvals)
+}
+
+/// The approval threshold of the need of the commitment, as validation reads it from the version the commitment references
+pub fn get_approval_threshold(commitment: &Commitment) -> ExternResult<u32> {
+    let call_to_action_version_hash = commitment
+        .call_to_action_version_hash
+        .clone()
+        .unwrap_or(commitment.call_to_action_hash.clone());
+    let call_to_action_record = get(call_to_action_version_hash, GetOptions::default())?.ok_or(
+        AssembleError::CallToActionNotFound(commitment.call_to_action_hash.clone()),
+    )?;
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    let need = call_to_action
+        .needs
+        .get(commitment.need_index as usize)
+        .ok_or(AssembleError::NeedNotFound {
+            call_to_action_hash: commitment.call_to_action_hash.clone(),
+            need_index: commitment.need_index,
+        })?;
+    Ok(need.approval_threshold)
+}
+
+pub fn is_commitment_approved(
+    commitment_hash: ActionHash,
+    approval_threshold: u32,
+) -> ExternResult<bool> {
+    Ok(get_commitment_approvals(commitment_hash)?.len() as u32 >= approval_threshold)
+}
+
+pub fn is_commitment_rejected(commitment_hash: ActionHash) -> ExternResult<bool> {
+    Ok(!get_rejections_for_commitment(commitment_hash)?.is_empty())
+}
+
+/// Returns the uncancelled commitments that are still waiting for the given admin to approve or reject them,
+/// across all the open calls to action that they author or administer
+#[hdk_extern]
+pub fn get_pending_approvals_for_admin(admin: AgentPubKey) -> ExternResult<Vec<ActionHash>> {
+    let call_to_action_hashes: BTreeSet<ActionHash> = get_calls_to_action_for_agent(admin.clone())?
+        .into_iter()
+        .chain(get_calls_to_action_for_admin(admin.clone())?)
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+
+    let mut pending_commitments: Vec<ActionHash> = vec![];
+    for call_to_action_hash in call_to_action_hashes {
+        if get_call_to_action_status(call_to_action_hash.clone())?.ne(&CallToActionStatus::Open) {
+            continue;
+        }
+        let Some(original_record) = get(call_to_action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+            AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+        )?;
+        let call_to_action = CallToAction::try_from(call_to_action_record)?;
+
+        // The admin links are validated against the version that added the admin, which might not be the latest,
+        // but the admins can't change anymore once a need requires their approval
+        if !is_author_or_admin(original_record.action().author(), &call_to_action, &admin) {
+            continue;
+        }
+
+        for (need_index, need) in call_to_action.needs.iter().enumerate() {
+            if !need.requires_admin_approval {
+                continue;
+            }
+            for (commitment_hash, _) in get_uncancelled_commitments_for_need(
+                call_to_action_hash.clone(),
+                need_index as u32,
+            )? {
+                let approvals = get_commitment_approvals(commitment_hash.clone())?;
+                if !approvals.contains_key(&admin)
+                    && (approvals.len() as u32) < need.approval_threshold
+                    && !is_commitment_rejected(commitment_hash.clone())?
+                {
+                    pending_commitments.push(commitment_hash);
+                }
+            }
+        }
+    }
+
+    Ok(pending_commitments)
+}
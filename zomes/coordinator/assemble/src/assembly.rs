@@ -1,8 +1,31 @@
 use assemble_integrity::*;
 use hdk::prelude::*;
+
+use crate::call_to_action::{get_latest_call_to_action, CallToActionStatus};
+use crate::error::AssembleError;
+use crate::index::update_call_to_action_index;
+use crate::satisfaction::get_canonical_satisfactions_for_call_to_action;
+use std::collections::BTreeSet;
 #[hdk_extern]
-pub fn create_assembly(assembly: Assembly) -> ExternResult<Record> {
+pub fn create_assembly(mut assembly: Assembly) -> ExternResult<Record> {
+    // Validation reads the policies from the version of the call to action that the assembly references
+    if assembly.call_to_action_version_hash.is_none() {
+        let call_to_action_record =
+            get_latest_call_to_action(assembly.call_to_action_hash.clone())?.ok_or(
+                AssembleError::CallToActionNotFound(assembly.call_to_action_hash.clone()),
+            )?;
+        assembly.call_to_action_version_hash = Some(call_to_action_record.action_address().clone());
+    }
     let assembly_hash = create_entry(&EntryTypes::Assembly(assembly.clone()))?;
+    index_assembly(assembly_hash.clone(), &assembly)?;
+    let record = get(assembly_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the newly created Assembly"))
+    ))?;
+    Ok(record)
+}
+/// Links the assembly from its call to action, its satisfactions and its agents,
+/// and moves its call to action to the fulfilled index
+pub fn index_assembly(assembly_hash: ActionHash, assembly: &Assembly) -> ExternResult<()> {
     create_link(
         assembly.call_to_action_hash.clone(),
         assembly_hash.clone(),
@@ -17,15 +40,99 @@ pub fn create_assembly(assembly: Assembly) -> ExternResult<Record> {
             (),
         )?;
     }
-    let record = get(assembly_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest(String::from("Could not find the newly created Assembly"))
-    ))?;
-    Ok(record)
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    for agent in get_assembly_agents(&my_pub_key, assembly)? {
+        create_link(
+            agent,
+            assembly_hash.clone(),
+            LinkTypes::AgentToAssemblies,
+            (),
+        )?;
+    }
+    let call_to_action_record = get_latest_call_to_action(assembly.call_to_action_hash.clone())?
+        .ok_or(AssembleError::CallToActionNotFound(
+            assembly.call_to_action_hash.clone(),
+        ))?;
+    update_call_to_action_index(
+        assembly.call_to_action_hash.clone(),
+        &CallToAction::try_from(call_to_action_record)?,
+        &CallToActionStatus::Fulfilled,
+    )?;
+    Ok(())
 }
 #[hdk_extern]
 pub fn get_assembly(assembly_hash: ActionHash) -> ExternResult<Option<Record>> {
     get(assembly_hash, GetOptions::default())
 }
+/// Deletes the assembly and its links, for when one of its satisfactions has been deleted
+pub fn invalidate_assembly(assembly_hash: ActionHash) -> ExternResult<()> {
+    let record = get_assembly(assembly_hash.clone())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("Could not find the Assembly")
+    )))?;
+    let author = record.action().author().clone();
+    let assembly = Assembly::try_from(record)?;
+
+    // Validation only lets the author of the assembly delete the links from its agents
+    if author.eq(&agent_info()?.agent_latest_pubkey) {
+        for agent in get_assembly_agents(&author, &assembly)? {
+            let links = get_links(agent, LinkTypes::AgentToAssemblies, None)?;
+            for link in links {
+                if let Some(target) = link.target.into_action_hash() {
+                    if target.eq(&assembly_hash) {
+                        delete_link(link.create_link_hash)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let links = get_links(
+        assembly.call_to_action_hash,
+        LinkTypes::CallToActionToAssemblies,
+        None,
+    )?;
+    for link in links {
+        if let Some(target) = link.target.into_action_hash() {
+            if target.eq(&assembly_hash) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+
+    for satisfaction_hash in assembly.satisfactions_hashes {
+        let links = get_links(satisfaction_hash, LinkTypes::SatisfactionToAssemblies, None)?;
+        for link in links {
+            if let Some(target) = link.target.into_action_hash() {
+                if target.eq(&assembly_hash) {
+                    delete_link(link.create_link_hash)?;
+                }
+            }
+        }
+    }
+
+    delete_entry(assembly_hash)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn get_assemblies_for_agent(agent: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(agent, LinkTypes::AgentToAssemblies, None)
+}
+
+#[hdk_extern]
+pub fn get_assembly_deletes(assembly_hash: ActionHash) -> ExternResult<Vec<SignedActionHashed>> {
+    let details = get_details(assembly_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("NOT_FOUND".into())))?;
+    let record_details = match details {
+        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
+            "Malformed details".into()
+        ))),
+        Details::Record(record_details) => Ok(record_details),
+    }?;
+    Ok(record_details.deletes)
+}
+
 #[hdk_extern]
 pub fn get_assemblies_for_call_to_action(
     call_to_action_hash: ActionHash,
@@ -37,6 +144,44 @@ pub fn get_assemblies_for_call_to_action(
     )
 }
 
+/// Returns the canonical assembly: when several agents assemble the same call to action concurrently,
+/// the canonical one is the assembly with the lowest action hash among the ones built only with canonical satisfactions,
+/// or among all of them if there is none
+#[hdk_extern]
+pub fn get_canonical_assembly_for_call_to_action(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<Option<Link>> {
+    let mut links = get_assemblies_for_call_to_action(call_to_action_hash.clone())?;
+    links.sort_by(|a, b| a.target.cmp(&b.target));
+    if links.len() < 2 {
+        return Ok(links.into_iter().next());
+    }
+
+    let canonical_satisfactions: BTreeSet<ActionHash> =
+        get_canonical_satisfactions_for_call_to_action(call_to_action_hash)?
+            .into_iter()
+            .filter_map(|l| l.target.into_action_hash())
+            .collect();
+    for link in links.iter() {
+        let Some(assembly_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get_assembly(assembly_hash)? else {
+            continue;
+        };
+        let assembly = Assembly::try_from(record)?;
+        if assembly
+            .satisfactions_hashes
+            .iter()
+            .all(|hash| canonical_satisfactions.contains(hash))
+        {
+            return Ok(Some(link.clone()));
+        }
+    }
+
+    Ok(links.into_iter().next())
+}
+
 #[hdk_extern]
 pub fn get_assemblies_for_satisfaction(satisfaction_hash: ActionHash) -> ExternResult<Vec<Link>> {
     get_links(satisfaction_hash, LinkTypes::SatisfactionToAssemblies, None)
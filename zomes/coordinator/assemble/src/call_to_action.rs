@@ -1,6 +1,17 @@
 use assemble_integrity::*;
 use hdk::prelude::*;
 
+use crate::admin::create_admin_changes_link;
+use crate::assembly::get_assemblies_for_call_to_action;
+use crate::cancellations::get_cancellations_for;
+use crate::commitment::{amount_committed, get_uncancelled_commitments_for_need};
+use crate::index::{
+    index_new_call_to_action, update_admins_index, update_call_to_action_index,
+    update_expiration_index, update_tags_index,
+};
+use crate::location::update_location_index;
+use crate::satisfaction::{get_latest_satisfaction, get_satisfactions_for_call_to_action};
+
 #[hdk_extern]
 pub fn create_call_to_action(call_to_action: CallToAction) -> ExternResult<Record> {
     let call_to_action_hash = create_entry(&EntryTypes::CallToAction(call_to_action.clone()))?;
@@ -12,18 +23,29 @@ pub fn create_call_to_action(call_to_action: CallToAction) -> ExternResult<Recor
             (),
         )?;
     }
+    create_link(
+        agent_info()?.agent_latest_pubkey,
+        call_to_action_hash.clone(),
+        LinkTypes::AgentToCallsToAction,
+        (),
+    )?;
     let record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
         WasmErrorInner::Guest(String::from(
             "Could not find the newly created CallToAction"
         ))
     ))?;
+    index_new_call_to_action(
+        call_to_action_hash.clone(),
+        &call_to_action,
+        record.action().timestamp(),
+    )?;
 
     Ok(record)
 }
 
 #[hdk_extern]
 pub fn get_latest_call_to_action(call_to_action_hash: ActionHash) -> ExternResult<Option<Record>> {
-    let Some(details )= get_details(call_to_action_hash, GetOptions::default())? else {
+    let Some(details) = get_details(call_to_action_hash, GetOptions::default())? else {
         return Ok(None);
     };
     let record_details = match details {
@@ -45,10 +67,69 @@ pub struct UpdateCallToActionInput {
 }
 #[hdk_extern]
 pub fn update_call_to_action(input: UpdateCallToActionInput) -> ExternResult<Record> {
+    let previous_record = get(
+        input.previous_call_to_action_hash.clone(),
+        GetOptions::default(),
+    )?
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the previous CallToAction"
+    ))))?;
+    let previous_call_to_action = CallToAction::try_from(previous_record)?;
+    let original_call_to_action_hash =
+        get_original_call_to_action_hash(input.previous_call_to_action_hash.clone())?;
+
+    check_needs_can_be_updated(
+        original_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
+
+    // The index links reference the version of the call to action that holds their contents
     let updated_call_to_action_hash = update_entry(
         input.previous_call_to_action_hash,
         &input.updated_call_to_action,
     )?;
+    update_tags_index(
+        original_call_to_action_hash.clone(),
+        updated_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
+    update_expiration_index(
+        original_call_to_action_hash.clone(),
+        updated_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
+    update_location_index(
+        original_call_to_action_hash.clone(),
+        updated_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
+    update_admins_index(
+        original_call_to_action_hash.clone(),
+        updated_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
+
+    // Keep the expiration time in the open index up to date
+    let status = get_call_to_action_status(original_call_to_action_hash.clone())?;
+    if status.eq(&CallToActionStatus::Open) {
+        update_call_to_action_index(
+            original_call_to_action_hash.clone(),
+            &input.updated_call_to_action,
+            &status,
+        )?;
+    }
+
+    create_admin_changes_link(
+        original_call_to_action_hash,
+        updated_call_to_action_hash.clone(),
+        &previous_call_to_action,
+        &input.updated_call_to_action,
+    )?;
     let record = get(updated_call_to_action_hash.clone(), GetOptions::default())?.ok_or(
         wasm_error!(WasmErrorInner::Guest(String::from(
             "Could not find the newly updated CallToAction"
@@ -56,6 +137,89 @@ pub fn update_call_to_action(input: UpdateCallToActionInput) -> ExternResult<Rec
     )?;
     Ok(record)
 }
+
+/// Needs that already have commitments can't be removed, moved nor changed, except for their min_necessary,
+/// which can't be lowered below the amount that satisfied the need
+///
+/// Validation can't see the commitments, it relies on them referencing the version of the call to action they were made for
+fn check_needs_can_be_updated(
+    original_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    let satisfactions = get_satisfactions_for_call_to_action(original_call_to_action_hash.clone())?
+        .into_iter()
+        .filter_map(|l| l.target.into_action_hash())
+        .map(|hash| get_latest_satisfaction(hash))
+        .collect::<ExternResult<Vec<Record>>>()?
+        .into_iter()
+        .map(|record| Satisfaction::try_from(record))
+        .collect::<ExternResult<Vec<Satisfaction>>>()?;
+
+    for (need_index, need) in previous_call_to_action.needs.iter().enumerate() {
+        let commitments = get_uncancelled_commitments_for_need(
+            original_call_to_action_hash.clone(),
+            need_index as u32,
+        )?;
+        if commitments.is_empty() {
+            continue;
+        }
+
+        let Some(updated_need) = updated_call_to_action.needs.get(need_index) else {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Can't remove need {} because it already has commitments",
+                need_index
+            ))));
+        };
+        let unchanged_need = Need {
+            min_necessary: need.min_necessary,
+            ..updated_need.clone()
+        };
+        if unchanged_need.ne(need) {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Can't change, replace or move need {} because it already has commitments",
+                need_index
+            ))));
+        }
+
+        if updated_need.min_necessary >= need.min_necessary {
+            continue;
+        }
+        for satisfaction in satisfactions
+            .iter()
+            .filter(|s| s.need_index == need_index as u32)
+        {
+            let amount_satisfied = amount_committed(
+                commitments
+                    .iter()
+                    .filter(|(hash, _)| satisfaction.commitments_hashes.contains(hash)),
+            );
+            if updated_need.min_necessary < amount_satisfied {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Can't lower the min_necessary of need {} below the {} that have already satisfied it",
+                    need_index, amount_satisfied
+                ))));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Follows the updates of a call to action back to the action that created it
+pub fn get_original_call_to_action_hash(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<ActionHash> {
+    let record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the CallToAction"))
+    ))?;
+    match record.action() {
+        Action::Update(update) => {
+            get_original_call_to_action_hash(update.original_action_address.clone())
+        }
+        _ => Ok(call_to_action_hash),
+    }
+}
 #[hdk_extern]
 pub fn delete_call_to_action(original_call_to_action_hash: ActionHash) -> ExternResult<ActionHash> {
     delete_entry(original_call_to_action_hash)
@@ -70,3 +234,58 @@ pub fn get_call_to_actions_for_call_to_action(
         None,
     )
 }
+#[hdk_extern]
+pub fn get_calls_to_action_for_agent(agent: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(agent, LinkTypes::AgentToCallsToAction, None)
+}
+#[hdk_extern]
+pub fn get_calls_to_action_for_admin(admin: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(admin, LinkTypes::AdminToCallsToAction, None)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum CallToActionStatus {
+    Open,
+    Expired,
+    Fulfilled,
+    Cancelled,
+}
+
+/// Expiration is read from the latest version of the call to action, which is the version that new commitments
+/// and satisfactions reference, and since updates can only postpone it no older version expires later
+#[hdk_extern]
+pub fn get_call_to_action_status(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<CallToActionStatus> {
+    let details = get_details(call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+        wasm_error!(WasmErrorInner::Guest("Call to action not found".into())),
+    )?;
+    let record_details = match details {
+        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
+            "Malformed details".into()
+        ))),
+        Details::Record(record_details) => Ok(record_details),
+    }?;
+    if !record_details.deletes.is_empty()
+        || !get_cancellations_for(call_to_action_hash.clone())?.is_empty()
+    {
+        return Ok(CallToActionStatus::Cancelled);
+    }
+
+    if !get_assemblies_for_call_to_action(call_to_action_hash.clone())?.is_empty() {
+        return Ok(CallToActionStatus::Fulfilled);
+    }
+
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash)?.ok_or(
+        wasm_error!(WasmErrorInner::Guest("Call to action not found".into())),
+    )?;
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    if let Some(expiration_time) = call_to_action.expiration_time {
+        if sys_time()? > expiration_time {
+            return Ok(CallToActionStatus::Expired);
+        }
+    }
+
+    Ok(CallToActionStatus::Open)
+}
@@ -0,0 +1,172 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+use crate::call_to_action::get_latest_call_to_action;
+use crate::commitment::{amount_committed, get_commitment, get_uncancelled_commitments_for_need};
+use crate::error::{call_local_zome, AssembleError};
+use crate::satisfaction::{
+    create_satisfaction, delete_satisfaction, get_all_satisfactions_for_need,
+};
+
+/// Returns the live cancellations for the given action, read from the cancellations zome named in the DNA properties
+pub fn get_cancellations_for(cancelled_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+    call_local_zome(
+        DnaProperties::get()?.cancellations_zome_name,
+        "get_cancellations_for",
+        cancelled_hash,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CancelCommitmentInput {
+    pub commitment_hash: ActionHash,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CreateCancellationInput {
+    cancelled_hash: ActionHash,
+    reason: String,
+}
+
+/// Cancels the commitment in the cancellations zome, and updates the satisfactions and assemblies that relied on it
+#[hdk_extern]
+pub fn cancel_commitment(input: CancelCommitmentInput) -> ExternResult<Record> {
+    let cancellation: Record = call_local_zome(
+        DnaProperties::get()?.cancellations_zome_name,
+        "create_cancellation",
+        CreateCancellationInput {
+            cancelled_hash: input.commitment_hash.clone(),
+            reason: input.reason,
+        },
+    )?;
+    reconcile_satisfactions_for_commitment(input.commitment_hash)?;
+    Ok(cancellation)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UndoCommitmentCancellationInput {
+    pub commitment_hash: ActionHash,
+    pub cancellation_hash: ActionHash,
+}
+
+/// Undoes the cancellation in the cancellations zome, and satisfies the need of the commitment again if it can
+#[hdk_extern]
+pub fn undo_commitment_cancellation(input: UndoCommitmentCancellationInput) -> ExternResult<()> {
+    let _: serde::de::IgnoredAny = call_local_zome(
+        DnaProperties::get()?.cancellations_zome_name,
+        "undo_cancellation",
+        input.cancellation_hash,
+    )?;
+    reconcile_satisfactions_for_commitment(input.commitment_hash)
+}
+
+/// Brings the satisfactions for the need of the given commitment in line with its uncancelled commitments
+///
+/// `cancel_commitment` and `undo_commitment_cancellation` already call this,
+/// call it after cancelling a commitment or undoing its cancellation directly in the cancellations zome
+#[hdk_extern]
+pub fn reconcile_satisfactions_for_commitment(commitment_hash: ActionHash) -> ExternResult<()> {
+    let Some(record) = get_commitment(commitment_hash)? else {
+        return Ok(());
+    };
+    // Cancellations can target other entries, nothing to do for those
+    let Ok(commitment) = Commitment::try_from(record) else {
+        return Ok(());
+    };
+
+    reconcile_satisfactions_for_need(commitment.call_to_action_hash, commitment.need_index)
+}
+
+/// Deletes the satisfactions for the need that rely on cancelled commitments, which invalidates the assemblies built on them,
+/// and satisfies the need again with the uncancelled commitments if they are enough
+///
+/// Needs that require admin approval are only satisfied again by the admins, and needs of expired
+/// calls to action or that don't count towards the fulfillment policy aren't satisfied again
+fn reconcile_satisfactions_for_need(
+    call_to_action_hash: ActionHash,
+    need_index: u32,
+) -> ExternResult<()> {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action_version_hash = call_to_action_record.action_address().clone();
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    let need =
+        call_to_action
+            .needs
+            .get(need_index as usize)
+            .ok_or(AssembleError::NeedNotFound {
+                call_to_action_hash: call_to_action_hash.clone(),
+                need_index,
+            })?;
+
+    let satisfactions = get_all_satisfactions_for_need(call_to_action_hash.clone(), need_index)?;
+    if satisfactions.is_empty() && need.requires_admin_approval {
+        return Ok(());
+    }
+
+    let commitments =
+        get_uncancelled_commitments_for_need(call_to_action_hash.clone(), need_index)?;
+
+    if need.requires_admin_approval {
+        // The admins decide again which commitments satisfy the need
+        for (satisfaction_hash, satisfaction) in satisfactions {
+            if !satisfaction
+                .commitments_hashes
+                .iter()
+                .all(|hash| commitments.iter().any(|(h, _)| h.eq(hash)))
+            {
+                delete_satisfaction(satisfaction_hash)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let amount_contributed = amount_committed(&commitments);
+    let mut commitments_hashes: Vec<ActionHash> =
+        commitments.into_iter().map(|(hash, _)| hash).collect();
+    commitments_hashes.sort();
+
+    if amount_contributed < need.amount_to_satisfy() {
+        for (satisfaction_hash, _) in satisfactions {
+            delete_satisfaction(satisfaction_hash)?;
+        }
+        return Ok(());
+    }
+
+    // Satisfactions that rely on cancelled commitments are replaced by one that doesn't
+    let mut is_satisfied = false;
+    for (satisfaction_hash, satisfaction) in satisfactions {
+        if satisfaction
+            .commitments_hashes
+            .iter()
+            .all(|hash| commitments_hashes.contains(hash))
+        {
+            is_satisfied = true;
+        } else {
+            delete_satisfaction(satisfaction_hash)?;
+        }
+    }
+
+    // Validation rejects satisfactions for expired calls to action, and the needs that don't count
+    // towards the fulfillment policy aren't satisfied in the first place
+    let has_expired = match call_to_action.expiration_time {
+        Some(expiration_time) => sys_time()? > expiration_time,
+        None => false,
+    };
+    if !is_satisfied
+        && !has_expired
+        && does_fulfillment_policy_count_need(&call_to_action, need_index)
+    {
+        create_satisfaction(Satisfaction {
+            call_to_action_hash,
+            need_index,
+            commitments_hashes,
+            approvals_hashes: vec![],
+            call_to_action_version_hash: Some(call_to_action_version_hash),
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+use crate::assembly::get_canonical_assembly_for_call_to_action;
+use crate::call_to_action::{
+    get_call_to_action_status, get_call_to_actions_for_call_to_action, CallToActionStatus,
+};
+
+/// Subtrees deeper than this are cut off so that a long chain of children can't exhaust the call
+pub const MAX_SUBTREE_DEPTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CallToActionTree {
+    pub call_to_action_hash: ActionHash,
+    pub status: CallToActionStatus,
+    pub children: Vec<CallToActionTree>,
+}
+
+#[hdk_extern]
+pub fn get_call_to_action_subtree(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<CallToActionTree> {
+    build_subtree(call_to_action_hash, 0)
+}
+
+fn build_subtree(call_to_action_hash: ActionHash, depth: usize) -> ExternResult<CallToActionTree> {
+    let status = get_call_to_action_status(call_to_action_hash.clone())?;
+
+    let mut children: Vec<CallToActionTree> = vec![];
+    if depth < MAX_SUBTREE_DEPTH {
+        for child_hash in get_children_hashes(call_to_action_hash.clone())? {
+            children.push(build_subtree(child_hash, depth + 1)?);
+        }
+    }
+
+    Ok(CallToActionTree {
+        call_to_action_hash,
+        status,
+        children,
+    })
+}
+
+fn get_children_hashes(call_to_action_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+    let mut children_hashes: Vec<ActionHash> =
+        get_call_to_actions_for_call_to_action(call_to_action_hash)?
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+            .collect();
+    children_hashes.sort();
+    children_hashes.dedup();
+    Ok(children_hashes)
+}
+
+/// Returns the canonical assemblies of the children that the children policy of the call to action
+/// requires, or None if any of them hasn't assembled yet
+///
+/// Cancelled children are not required by the AllChildren policy
+pub fn get_required_children_assemblies(
+    call_to_action_hash: ActionHash,
+    call_to_action: &CallToAction,
+) -> ExternResult<Option<Vec<ActionHash>>> {
+    let required_children_hashes = match &call_to_action.children_policy {
+        ChildrenPolicy::Independent => return Ok(Some(vec![])),
+        ChildrenPolicy::AllChildren => {
+            let mut children_hashes: Vec<ActionHash> = vec![];
+            for child_hash in get_children_hashes(call_to_action_hash)? {
+                if get_call_to_action_status(child_hash.clone())?.ne(&CallToActionStatus::Cancelled)
+                {
+                    children_hashes.push(child_hash);
+                }
+            }
+            children_hashes
+        }
+        ChildrenPolicy::SomeChildren { children_hashes } => {
+            let mut children_hashes = children_hashes.clone();
+            children_hashes.sort();
+            children_hashes.dedup();
+            children_hashes
+        }
+    };
+
+    let mut children_assemblies_hashes: Vec<ActionHash> = vec![];
+    for child_hash in required_children_hashes {
+        let Some(assembly_hash) = get_canonical_assembly_for_call_to_action(child_hash)?
+            .and_then(|link| link.target.into_action_hash())
+        else {
+            return Ok(None);
+        };
+        children_assemblies_hashes.push(assembly_hash);
+    }
+
+    Ok(Some(children_assemblies_hashes))
+}
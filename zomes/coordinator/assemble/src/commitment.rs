@@ -1,13 +1,57 @@
 use assemble_integrity::*;
 use hdk::prelude::*;
+use std::collections::HashSet;
 
+use crate::call_to_action::get_latest_call_to_action;
+use crate::cancellations::get_cancellations_for;
+
+/// Rejects commitments that would make the committed amount exceed the max_possible of the need,
+/// this is only a best effort check since validation can't see the other commitments for the need
 #[hdk_extern]
-pub fn create_commitment(commitment: Commitment) -> ExternResult<Record> {
+pub fn create_commitment(mut commitment: Commitment) -> ExternResult<Record> {
+    let call_to_action_record = get_latest_call_to_action(commitment.call_to_action_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not find call to action for this commitment".into()
+        )))?;
+    // Validation reads the need from the version of the call to action that the commitment references
+    if commitment.call_to_action_version_hash.is_none() {
+        commitment.call_to_action_version_hash =
+            Some(call_to_action_record.action_address().clone());
+    }
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    let need = call_to_action
+        .needs
+        .get(commitment.need_index as usize)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "The need_index of this commitment does not exist in its call to action".into()
+        )))?;
+
+    if let Some(max_possible) = need.max_possible {
+        let amount_committed = amount_committed(&get_uncancelled_commitments_for_need(
+            commitment.call_to_action_hash.clone(),
+            commitment.need_index,
+        )?);
+
+        if amount_committed.saturating_add(commitment.amount) > max_possible {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "This commitment would exceed the max_possible for its need: {} out of {} are already committed",
+                amount_committed, max_possible
+            ))));
+        }
+    }
+
     let commitment_hash = create_entry(&EntryTypes::Commitment(commitment.clone()))?;
+    let tag = CommitmentLinkTag::new(&commitment, agent_info()?.agent_latest_pubkey);
     create_link(
         commitment.call_to_action_hash.clone(),
         commitment_hash.clone(),
         LinkTypes::CallToActionToCommitments,
+        LinkTag::try_from(tag)?,
+    )?;
+    create_link(
+        agent_info()?.agent_latest_pubkey,
+        commitment_hash.clone(),
+        LinkTypes::AgentToCommitments,
         (),
     )?;
 
@@ -32,3 +76,63 @@ pub fn get_commitments_for_call_to_action(
         None,
     )
 }
+
+#[hdk_extern]
+pub fn get_commitments_for_agent(agent: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(agent, LinkTypes::AgentToCommitments, None)
+}
+
+/// Returns the tags of the commitments for the given call to action that haven't been cancelled
+///
+/// The need index, amount and committer are read from the link tags, so the commitments are not fetched
+pub fn get_uncancelled_commitments_for_call_to_action(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<Vec<(ActionHash, CommitmentLinkTag)>> {
+    get_uncancelled_commitments(call_to_action_hash, |_| true)
+}
+
+/// Returns the tags of the commitments for the given need that haven't been cancelled
+pub fn get_uncancelled_commitments_for_need(
+    call_to_action_hash: ActionHash,
+    need_index: u32,
+) -> ExternResult<Vec<(ActionHash, CommitmentLinkTag)>> {
+    get_uncancelled_commitments(call_to_action_hash, |tag| tag.need_index == need_index)
+}
+
+/// Sums the amounts of the given commitments, saturating instead of overflowing
+pub fn amount_committed<'a>(
+    commitments: impl IntoIterator<Item = &'a (ActionHash, CommitmentLinkTag)>,
+) -> u32 {
+    commitments
+        .into_iter()
+        .fold(0u32, |acc, (_, c)| acc.saturating_add(c.amount))
+}
+
+/// Only the commitments that pass the filter are checked for cancellations,
+/// and the same commitment is only counted once even if it's linked more than once
+fn get_uncancelled_commitments(
+    call_to_action_hash: ActionHash,
+    filter: impl Fn(&CommitmentLinkTag) -> bool,
+) -> ExternResult<Vec<(ActionHash, CommitmentLinkTag)>> {
+    let links = get_commitments_for_call_to_action(call_to_action_hash)?;
+
+    let mut commitments_hashes: HashSet<ActionHash> = HashSet::new();
+    let mut commitments: Vec<(ActionHash, CommitmentLinkTag)> = vec![];
+    for link in links {
+        let Some(commitment_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        // Validation rejects the links without a CommitmentLinkTag
+        let Ok(tag) = CommitmentLinkTag::try_from(link.tag) else {
+            continue;
+        };
+        if !filter(&tag) || !commitments_hashes.insert(commitment_hash.clone()) {
+            continue;
+        }
+        if get_cancellations_for(commitment_hash.clone())?.is_empty() {
+            commitments.push((commitment_hash, tag));
+        }
+    }
+
+    Ok(commitments)
+}
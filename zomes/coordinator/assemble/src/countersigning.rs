@@ -0,0 +1,277 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+use crate::assembly::{get_assemblies_for_call_to_action, index_assembly};
+use crate::build_assembly_if_fulfilled;
+use crate::call_to_action::get_latest_call_to_action;
+use crate::error::{call_remote_zome, AssembleError};
+
+/// How long the other signers have to accept and commit the countersigned assembly
+pub const COUNTERSIGNING_SESSION_MILLIS: u64 = 30_000;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcceptAssemblyCountersigningInput {
+    pub assembly: Assembly,
+    pub preflight_request: PreflightRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommitCountersignedAssemblyInput {
+    pub assembly: Assembly,
+    pub responses: Vec<PreflightResponse>,
+}
+
+/// Starts a countersigning session for the assembly of a fulfilled call to action that requires it,
+/// collecting the signatures of its author and all its admins
+///
+/// Only its author or one of its admins can start the session, and all of them need to be online
+#[hdk_extern]
+pub fn create_countersigned_assembly(call_to_action_hash: ActionHash) -> ExternResult<Record> {
+    if !get_assemblies_for_call_to_action(call_to_action_hash.clone())?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "This call to action has already been assembled"
+        ))));
+    }
+    let original_record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let author = original_record.action().author().clone();
+    let original_call_to_action = CallToAction::try_from(original_record)?;
+    if !original_call_to_action.countersigned_assembly {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "This call to action doesn't require a countersigned assembly"
+        ))));
+    }
+
+    let signing_agents = get_signing_agents(&author, &original_call_to_action);
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if !signing_agents.contains(&my_pub_key) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Only the author or the admins of the call to action can start its countersigned assembly"
+        ))));
+    }
+
+    let assembly = build_countersigned_assembly(call_to_action_hash)?;
+
+    // The initiator goes first, so that it's the one that indexes the assembly after the session
+    let signing_agents: Vec<AgentPubKey> = std::iter::once(my_pub_key.clone())
+        .chain(signing_agents.into_iter().filter(|a| a.ne(&my_pub_key)))
+        .collect();
+    let preflight_request = PreflightRequest::try_new(
+        hash_entry(&assembly)?,
+        signing_agents
+            .iter()
+            .map(|agent| (agent.clone(), vec![]))
+            .collect(),
+        vec![],
+        0,
+        false,
+        session_times_from_millis(COUNTERSIGNING_SESSION_MILLIS)?,
+        ActionBase::Create(CreateBase::new(UnitEntryTypes::Assembly.try_into()?)),
+        PreflightBytes(vec![]),
+    )
+    .map_err(|err| wasm_error!(WasmErrorInner::Guest(err.to_string())))?;
+
+    let mut responses = vec![accept_preflight_request(preflight_request.clone())?];
+    for agent in signing_agents.iter().skip(1) {
+        let response: PreflightResponse = call_remote_zome(
+            agent.clone(),
+            zome_info()?.name,
+            "accept_assembly_countersigning",
+            AcceptAssemblyCountersigningInput {
+                assembly: assembly.clone(),
+                preflight_request: preflight_request.clone(),
+            },
+        )?;
+        responses.push(response);
+    }
+
+    for agent in signing_agents.iter().skip(1) {
+        let _: ActionHash = call_remote_zome(
+            agent.clone(),
+            zome_info()?.name,
+            "commit_countersigned_assembly",
+            CommitCountersignedAssemblyInput {
+                assembly: assembly.clone(),
+                responses: responses.clone(),
+            },
+        )?;
+    }
+
+    let assembly_hash = commit_countersigned_assembly(CommitCountersignedAssemblyInput {
+        assembly,
+        responses,
+    })?;
+
+    let record = get(assembly_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the newly created Assembly"))
+    ))?;
+    Ok(record)
+}
+
+/// Called by the initiator of a countersigned assembly on the other signers,
+/// which only accept to sign assemblies for unassembled calls to action that require it
+///
+/// Anyone can call this function and accepting locks my source chain for the session,
+/// so the session must be started by one of the signers, be short, and be for the assembly I would build myself
+#[hdk_extern]
+pub fn accept_assembly_countersigning(
+    input: AcceptAssemblyCountersigningInput,
+) -> ExternResult<PreflightResponse> {
+    if hash_entry(&input.assembly)?.ne(input.preflight_request.app_entry_hash()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The preflight request is not for the given assembly"
+        ))));
+    }
+    let call_to_action_hash = input.assembly.call_to_action_hash.clone();
+    let original_record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let author = original_record.action().author().clone();
+    let call_to_action = CallToAction::try_from(original_record)?;
+    if !call_to_action.countersigned_assembly {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "This call to action doesn't require a countersigned assembly"
+        ))));
+    }
+    if !get_assemblies_for_call_to_action(call_to_action_hash.clone())?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "This call to action has already been assembled"
+        ))));
+    }
+
+    let signing_agents = get_signing_agents(&author, &call_to_action);
+    let session_agents: Vec<&AgentPubKey> = input
+        .preflight_request
+        .signing_agents()
+        .iter()
+        .map(|(agent, _)| agent)
+        .collect();
+    let initiator = call_info()?.provenance;
+    if session_agents.first().ne(&Some(&&initiator)) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Only the initiator of the countersigning session can ask me to accept it"
+        ))));
+    }
+    if session_agents.len() != signing_agents.len()
+        || !signing_agents
+            .iter()
+            .all(|agent| session_agents.contains(&agent))
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The signers of the countersigning session must be the author and the admins of the call to action"
+        ))));
+    }
+
+    let session_times = input.preflight_request.session_times();
+    let session_micros = session_times
+        .end()
+        .as_micros()
+        .saturating_sub(session_times.start().as_micros());
+    if session_micros < 0 || session_micros as u64 > COUNTERSIGNING_SESSION_MILLIS * 1000 {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "The countersigning session can't last longer than {} milliseconds",
+            COUNTERSIGNING_SESSION_MILLIS
+        ))));
+    }
+
+    if build_countersigned_assembly(call_to_action_hash)?.ne(&input.assembly) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The assembly of the countersigning session is not the one I would build for this call to action"
+        ))));
+    }
+
+    accept_preflight_request(input.preflight_request)
+}
+
+/// Commits the countersigned assembly with the responses of all the signers to the preflight request
+#[hdk_extern]
+pub fn commit_countersigned_assembly(
+    input: CommitCountersignedAssemblyInput,
+) -> ExternResult<ActionHash> {
+    let session_data = CounterSigningSessionData::try_from_responses(input.responses, vec![])
+        .map_err(|err| wasm_error!(WasmErrorInner::Guest(err.to_string())))?;
+    let EntryType::App(app_entry_def) = UnitEntryTypes::Assembly.try_into()? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Assembly is not an app entry type"
+        ))));
+    };
+    let entry = Entry::CounterSign(
+        Box::new(session_data),
+        Box::new(AppEntryBytes(
+            SerializedBytes::try_from(input.assembly).map_err(|err| wasm_error!(err))?,
+        )),
+    );
+    create(CreateInput::new(
+        EntryDefLocation::app(app_entry_def.zome_index, app_entry_def.entry_index),
+        EntryVisibility::Public,
+        entry,
+        ChainTopOrdering::Strict,
+    ))
+}
+
+/// Indexes the assembly if it was countersigned and I initiated its session,
+/// since every signer commits its own action for the same countersigned entry
+///
+/// Called from post_commit through a zome call, since post_commit can't write to the source chain itself
+#[hdk_extern]
+pub fn index_countersigned_assembly(assembly_hash: ActionHash) -> ExternResult<()> {
+    let Some(record) = get(assembly_hash.clone(), GetOptions::default())? else {
+        return Ok(());
+    };
+    let Some(Entry::CounterSign(session_data, app_entry_bytes)) = record.entry().as_option() else {
+        return Ok(());
+    };
+    let initiator = session_data.signing_agents().next();
+    if initiator.ne(&Some(&agent_info()?.agent_latest_pubkey)) {
+        return Ok(());
+    }
+    let assembly = Assembly::try_from(app_entry_bytes.0.clone()).map_err(|err| wasm_error!(err))?;
+    index_assembly(assembly_hash, &assembly)
+}
+
+/// Builds the assembly for the latest version of the call to action, failing if it is not fulfilled
+fn build_countersigned_assembly(call_to_action_hash: ActionHash) -> ExternResult<Assembly> {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action_version_hash = call_to_action_record.action_address().clone();
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    build_assembly_if_fulfilled(
+        call_to_action_hash,
+        call_to_action_version_hash,
+        &call_to_action,
+        None,
+    )?
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "This call to action is not fulfilled yet"
+    ))))
+}
+
+/// The agents that must countersign the assembly, as validation reads them from the original call to action
+fn get_signing_agents(author: &AgentPubKey, call_to_action: &CallToAction) -> Vec<AgentPubKey> {
+    let mut signing_agents = vec![author.clone()];
+    for admin in call_to_action.admins.iter() {
+        if !signing_agents.contains(admin) {
+            signing_agents.push(admin.clone());
+        }
+    }
+    signing_agents
+}
+
+fn accept_preflight_request(
+    preflight_request: PreflightRequest,
+) -> ExternResult<PreflightResponse> {
+    match accept_countersigning_preflight_request(preflight_request)? {
+        PreflightRequestAcceptance::Accepted(response) => Ok(response),
+        PreflightRequestAcceptance::UnacceptableFutureStart => Err(wasm_error!(
+            WasmErrorInner::Guest(String::from("The countersigning session starts too late"))
+        )),
+        PreflightRequestAcceptance::UnacceptableAgentNotFound => Err(wasm_error!(
+            WasmErrorInner::Guest(String::from("I'm not one of the signers of this session"))
+        )),
+        PreflightRequestAcceptance::Invalid(reason) => Err(wasm_error!(WasmErrorInner::Guest(
+            format!("Invalid countersigning preflight request: {}", reason)
+        ))),
+    }
+}
@@ -0,0 +1,142 @@
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AssembleError {
+    CallToActionNotFound(ActionHash),
+    NeedNotFound {
+        call_to_action_hash: ActionHash,
+        need_index: u32,
+    },
+    UnauthorizedZomeCall {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+    },
+    ZomeCallNetworkError {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        error: String,
+    },
+    ZomeCallCountersigningError {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssembleError::CallToActionNotFound(hash) => {
+                write!(f, "Could not find the call to action {}", hash)
+            }
+            AssembleError::NeedNotFound {
+                call_to_action_hash,
+                need_index,
+            } => write!(
+                f,
+                "The need {} does not exist in the call to action {}",
+                need_index, call_to_action_hash
+            ),
+            AssembleError::UnauthorizedZomeCall { zome_name, fn_name } => {
+                write!(f, "Unauthorized to call {}/{}", zome_name.0, fn_name.0)
+            }
+            AssembleError::ZomeCallNetworkError {
+                zome_name,
+                fn_name,
+                error,
+            } => write!(
+                f,
+                "Network error calling {}/{}: {}",
+                zome_name.0, fn_name.0, error
+            ),
+            AssembleError::ZomeCallCountersigningError {
+                zome_name,
+                fn_name,
+                error,
+            } => write!(
+                f,
+                "Countersigning error calling {}/{}: {}",
+                zome_name.0, fn_name.0, error
+            ),
+        }
+    }
+}
+
+/// The error is serialized as JSON, so that callers can tell the different errors apart
+impl From<AssembleError> for WasmError {
+    fn from(err: AssembleError) -> WasmError {
+        match serde_json::to_string(&err) {
+            Ok(serialized_error) => wasm_error!(WasmErrorInner::Guest(serialized_error)),
+            Err(_) => wasm_error!(WasmErrorInner::Guest(err.to_string())),
+        }
+    }
+}
+
+/// Calls a function in another zome of this same cell
+pub fn call_local_zome<I, O>(
+    zome_name: impl Into<ZomeName>,
+    fn_name: impl Into<FunctionName>,
+    payload: I,
+) -> ExternResult<O>
+where
+    I: Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let zome_name: ZomeName = zome_name.into();
+    let fn_name: FunctionName = fn_name.into();
+    let response = call(
+        CallTargetCell::Local,
+        zome_name.clone(),
+        fn_name.clone(),
+        None,
+        payload,
+    )?;
+    decode_zome_call_response(zome_name, fn_name, response)
+}
+
+/// Calls a function in the cell of another agent of this same DNA
+pub fn call_remote_zome<I, O>(
+    agent: AgentPubKey,
+    zome_name: impl Into<ZomeName>,
+    fn_name: impl Into<FunctionName>,
+    payload: I,
+) -> ExternResult<O>
+where
+    I: Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let zome_name: ZomeName = zome_name.into();
+    let fn_name: FunctionName = fn_name.into();
+    let response = call_remote(agent, zome_name.clone(), fn_name.clone(), None, payload)?;
+    decode_zome_call_response(zome_name, fn_name, response)
+}
+
+fn decode_zome_call_response<O>(
+    zome_name: ZomeName,
+    fn_name: FunctionName,
+    response: ZomeCallResponse,
+) -> ExternResult<O>
+where
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|err| wasm_error!(err)),
+        ZomeCallResponse::Unauthorized(..) => {
+            Err(AssembleError::UnauthorizedZomeCall { zome_name, fn_name }.into())
+        }
+        ZomeCallResponse::NetworkError(error) => Err(AssembleError::ZomeCallNetworkError {
+            zome_name,
+            fn_name,
+            error,
+        }
+        .into()),
+        ZomeCallResponse::CountersigningSession(error) => {
+            Err(AssembleError::ZomeCallCountersigningError {
+                zome_name,
+                fn_name,
+                error,
+            }
+            .into())
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::call_to_action::{get_call_to_action_status, CallToActionStatus};
+use crate::location::create_location_links;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pagination {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Tag of the OpenCallsToAction links, so that expired calls to action can be filtered out
+/// without getting them
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+struct OpenCallToActionLinkTag {
+    expiration_time: Option<Timestamp>,
+}
+
+fn open_call_to_action_link_tag(call_to_action: &CallToAction) -> ExternResult<LinkTag> {
+    let bytes = SerializedBytes::try_from(OpenCallToActionLinkTag {
+        expiration_time: call_to_action.expiration_time,
+    })
+    .map_err(|e| wasm_error!(e))?;
+    Ok(LinkTag::new(bytes.bytes().clone()))
+}
+
+fn is_expired(link: &Link, now: &Timestamp) -> bool {
+    let Ok(tag) = OpenCallToActionLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(
+        link.tag.0.clone(),
+    ))) else {
+        return false;
+    };
+    match tag.expiration_time {
+        Some(expiration_time) => now > &expiration_time,
+        None => false,
+    }
+}
+
+/// Newest first, keeping only one link per call to action
+fn sort_and_dedup(mut links: Vec<Link>) -> Vec<Link> {
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    dedup_targets(links)
+}
+
+/// Keeps only the first link to each call to action
+fn dedup_targets(links: Vec<Link>) -> Vec<Link> {
+    let mut seen: Vec<AnyLinkableHash> = vec![];
+    links
+        .into_iter()
+        .filter(|l| {
+            if seen.contains(&l.target) {
+                false
+            } else {
+                seen.push(l.target.clone());
+                true
+            }
+        })
+        .collect()
+}
+
+fn paginate(links: Vec<Link>, pagination: Option<Pagination>) -> Vec<Link> {
+    match pagination {
+        Some(pagination) => links
+            .into_iter()
+            .skip(pagination.offset)
+            .take(pagination.limit)
+            .collect(),
+        None => links,
+    }
+}
+
+fn get_index_links_for(
+    path: Path,
+    link_type: LinkTypes,
+    call_to_action_hash: &ActionHash,
+) -> ExternResult<Vec<Link>> {
+    let target = AnyLinkableHash::from(call_to_action_hash.clone());
+    Ok(get_links(path.path_entry_hash()?, link_type, None)?
+        .into_iter()
+        .filter(|l| l.target.eq(&target))
+        .collect())
+}
+
+/// Adds a newly created call to action to the "all" and "open" indexes of the day in which it was created
+pub fn index_new_call_to_action(
+    call_to_action_hash: ActionHash,
+    call_to_action: &CallToAction,
+    created_at: Timestamp,
+) -> ExternResult<()> {
+    let day = creation_day(&created_at);
+    ensure_day_is_indexed(day)?;
+    create_link(
+        all_calls_to_action_day_path(day).path_entry_hash()?,
+        call_to_action_hash.clone(),
+        LinkTypes::AllCallsToAction,
+        (),
+    )?;
+    create_link(
+        open_calls_to_action_day_path(day).path_entry_hash()?,
+        call_to_action_hash.clone(),
+        LinkTypes::OpenCallsToAction,
+        open_call_to_action_link_tag(call_to_action)?,
+    )?;
+    for tag in call_to_action.tags.iter() {
+        create_tag_link(
+            call_to_action_hash.clone(),
+            call_to_action_hash.clone(),
+            tag,
+        )?;
+    }
+    if let Some(expiration_time) = call_to_action.expiration_time {
+        create_expiration_link(
+            call_to_action_hash.clone(),
+            call_to_action_hash.clone(),
+            expiration_time,
+        )?;
+    }
+    for admin in call_to_action.admins.iter() {
+        create_admin_link(
+            admin.clone(),
+            call_to_action_hash.clone(),
+            call_to_action_hash.clone(),
+        )?;
+    }
+    if let Some(location) = &call_to_action.location {
+        create_location_links(call_to_action_hash.clone(), call_to_action_hash, location)?;
+    }
+    Ok(())
+}
+
+/// Links the path of the day from the "all" index, unless it's already linked
+fn ensure_day_is_indexed(day: i64) -> ExternResult<()> {
+    let tag = LinkTag::try_from(DayLinkTag { day })?;
+    let links = get_links(
+        all_calls_to_action_path().path_entry_hash()?,
+        LinkTypes::AllCallsToActionToDays,
+        Some(tag.clone()),
+    )?;
+    if links.is_empty() {
+        create_link(
+            all_calls_to_action_path().path_entry_hash()?,
+            all_calls_to_action_day_path(day).path_entry_hash()?,
+            LinkTypes::AllCallsToActionToDays,
+            tag,
+        )?;
+    }
+    Ok(())
+}
+
+/// Days in which calls to action were created, newest first
+fn get_indexed_days() -> ExternResult<Vec<i64>> {
+    let days: BTreeSet<i64> = get_links(
+        all_calls_to_action_path().path_entry_hash()?,
+        LinkTypes::AllCallsToActionToDays,
+        None,
+    )?
+    .into_iter()
+    .filter_map(|l| DayLinkTag::try_from(l.tag).ok())
+    .map(|tag| tag.day)
+    .collect();
+    Ok(days.into_iter().rev().collect())
+}
+
+/// Reads the days newest first, only until the requested page is complete
+fn get_page_by_day(
+    pagination: Option<Pagination>,
+    get_links_of_day: impl Fn(i64) -> ExternResult<Vec<Link>>,
+) -> ExternResult<Vec<Link>> {
+    let mut links: Vec<Link> = vec![];
+    for day in get_indexed_days()? {
+        if let Some(pagination) = &pagination {
+            if links.len() >= pagination.offset.saturating_add(pagination.limit) {
+                break;
+            }
+        }
+        links.extend(get_links_of_day(day)?);
+    }
+    Ok(paginate(links, pagination))
+}
+
+/// The latest link to each call to action of the day in the "open" and "closed" indexes, newest first,
+/// paired with whether it's in the "open" index
+///
+/// An agent can't always delete the links that other agents created for the previous status,
+/// so the latest link is the one that holds
+fn get_latest_open_and_closed_links(day: i64) -> ExternResult<Vec<(bool, Link)>> {
+    let mut links: Vec<(bool, Link)> = get_links(
+        open_calls_to_action_day_path(day).path_entry_hash()?,
+        LinkTypes::OpenCallsToAction,
+        None,
+    )?
+    .into_iter()
+    .map(|l| (true, l))
+    .chain(
+        get_links(
+            closed_calls_to_action_day_path(day).path_entry_hash()?,
+            LinkTypes::ClosedCallsToAction,
+            None,
+        )?
+        .into_iter()
+        .map(|l| (false, l)),
+    )
+    .collect();
+    links.sort_by(|(_, a), (_, b)| b.timestamp.cmp(&a.timestamp));
+
+    let mut seen: BTreeSet<AnyLinkableHash> = BTreeSet::new();
+    Ok(links
+        .into_iter()
+        .filter(|(_, l)| seen.insert(l.target.clone()))
+        .collect())
+}
+
+fn create_admin_link(
+    admin: AgentPubKey,
+    call_to_action_hash: ActionHash,
+    call_to_action_version_hash: ActionHash,
+) -> ExternResult<()> {
+    create_link(
+        admin,
+        call_to_action_hash,
+        LinkTypes::AdminToCallsToAction,
+        LinkTag::try_from(AdminLinkTag {
+            call_to_action_version_hash,
+        })?,
+    )?;
+    Ok(())
+}
+
+fn create_tag_link(
+    call_to_action_hash: ActionHash,
+    call_to_action_version_hash: ActionHash,
+    tag: &String,
+) -> ExternResult<()> {
+    create_link(
+        tag_path(tag).path_entry_hash()?,
+        call_to_action_hash,
+        LinkTypes::TagToCallsToAction,
+        LinkTag::try_from(CallToActionTagLinkTag {
+            tag: tag.clone(),
+            call_to_action_version_hash,
+        })?,
+    )?;
+    Ok(())
+}
+
+fn create_expiration_link(
+    call_to_action_hash: ActionHash,
+    call_to_action_version_hash: ActionHash,
+    expiration_time: Timestamp,
+) -> ExternResult<()> {
+    create_link(
+        expiration_day_path(expiration_day(&expiration_time)).path_entry_hash()?,
+        call_to_action_hash,
+        LinkTypes::ExpirationToCallsToAction,
+        LinkTag::try_from(ExpirationLinkTag {
+            expiration_time,
+            call_to_action_version_hash,
+        })?,
+    )?;
+    Ok(())
+}
+
+/// Moves the call to action to the bucket of its new expiration time
+pub fn update_expiration_index(
+    call_to_action_hash: ActionHash,
+    updated_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    if previous_call_to_action
+        .expiration_time
+        .eq(&updated_call_to_action.expiration_time)
+    {
+        return Ok(());
+    }
+    if let Some(expiration_time) = previous_call_to_action.expiration_time {
+        let can_delete = index_links_deleter(get_original_record(&call_to_action_hash)?)?;
+        let links = get_index_links_for(
+            expiration_day_path(expiration_day(&expiration_time)),
+            LinkTypes::ExpirationToCallsToAction,
+            &call_to_action_hash,
+        )?;
+        for link in links {
+            if can_delete(&link) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    if let Some(expiration_time) = updated_call_to_action.expiration_time {
+        create_expiration_link(
+            call_to_action_hash,
+            updated_call_to_action_hash,
+            expiration_time,
+        )?;
+    }
+    Ok(())
+}
+
+/// Links the call to action from the indexes of its new tags and unlinks it from the ones it no longer has
+pub fn update_tags_index(
+    call_to_action_hash: ActionHash,
+    updated_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    let can_delete = index_links_deleter(get_original_record(&call_to_action_hash)?)?;
+    for tag in previous_call_to_action.tags.iter() {
+        if updated_call_to_action.tags.contains(tag) {
+            continue;
+        }
+        let links = get_index_links_for(
+            tag_path(tag),
+            LinkTypes::TagToCallsToAction,
+            &call_to_action_hash,
+        )?;
+        for link in links {
+            if can_delete(&link) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    for tag in updated_call_to_action.tags.iter() {
+        if !previous_call_to_action.tags.contains(tag) {
+            create_tag_link(
+                call_to_action_hash.clone(),
+                updated_call_to_action_hash.clone(),
+                tag,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Transferred owners are indexed with the admins, since the call to action is not linked from their agent
+pub fn update_admins_index(
+    call_to_action_hash: ActionHash,
+    updated_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    let previous_admins: BTreeSet<&AgentPubKey> = previous_call_to_action
+        .admins
+        .iter()
+        .chain(previous_call_to_action.owner.iter())
+        .collect();
+    let updated_admins: BTreeSet<&AgentPubKey> = updated_call_to_action
+        .admins
+        .iter()
+        .chain(updated_call_to_action.owner.iter())
+        .collect();
+    let can_delete = index_links_deleter(get_original_record(&call_to_action_hash)?)?;
+    for admin in previous_admins.difference(&updated_admins) {
+        let links = get_links((*admin).clone(), LinkTypes::AdminToCallsToAction, None)?;
+        for link in links {
+            if link
+                .target
+                .clone()
+                .into_action_hash()
+                .eq(&Some(call_to_action_hash.clone()))
+                && can_delete(&link)
+            {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    for admin in updated_admins.difference(&previous_admins) {
+        create_admin_link(
+            (*admin).clone(),
+            call_to_action_hash.clone(),
+            updated_call_to_action_hash.clone(),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn get_original_record(call_to_action_hash: &ActionHash) -> ExternResult<Record> {
+    get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the CallToAction"))
+    ))
+}
+
+/// Validation only lets the owner and the admins of the original call to action
+/// delete the index links created by other agents
+pub fn index_links_deleter(original_record: Record) -> ExternResult<impl Fn(&Link) -> bool> {
+    let author = original_record.action().author().clone();
+    let original_call_to_action = CallToAction::try_from(original_record)?;
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let is_admin = is_author_or_admin(&author, &original_call_to_action, &my_pub_key);
+    Ok(move |link: &Link| is_admin || link.author.eq(&my_pub_key))
+}
+
+/// Moves the call to action to the "open" or "closed" index depending on its status
+pub fn update_call_to_action_index(
+    call_to_action_hash: ActionHash,
+    call_to_action: &CallToAction,
+    status: &CallToActionStatus,
+) -> ExternResult<()> {
+    let original_record = get_original_record(&call_to_action_hash)?;
+    let day = creation_day(&original_record.action().timestamp());
+    let can_delete = index_links_deleter(original_record)?;
+
+    let open_links = get_index_links_for(
+        open_calls_to_action_day_path(day),
+        LinkTypes::OpenCallsToAction,
+        &call_to_action_hash,
+    )?;
+    let closed_links = get_index_links_for(
+        closed_calls_to_action_day_path(day),
+        LinkTypes::ClosedCallsToAction,
+        &call_to_action_hash,
+    )?;
+
+    if status.eq(&CallToActionStatus::Open) {
+        // The tag changes when the expiration time of the call to action is updated
+        keep_latest_index_link(
+            open_calls_to_action_day_path(day),
+            LinkTypes::OpenCallsToAction,
+            open_call_to_action_link_tag(call_to_action)?,
+            call_to_action_hash,
+            open_links,
+            closed_links,
+            can_delete,
+        )
+    } else {
+        keep_latest_index_link(
+            closed_calls_to_action_day_path(day),
+            LinkTypes::ClosedCallsToAction,
+            LinkTag::from(()),
+            call_to_action_hash,
+            closed_links,
+            open_links,
+            can_delete,
+        )
+    }
+}
+
+/// Keeps a link with the given tag in the index, as long as it's newer than the links that can't be deleted,
+/// or creates a new one, and deletes every other link to the call to action that can be deleted
+fn keep_latest_index_link(
+    path: Path,
+    link_type: LinkTypes,
+    tag: LinkTag,
+    call_to_action_hash: ActionHash,
+    index_links: Vec<Link>,
+    other_links: Vec<Link>,
+    can_delete: impl Fn(&Link) -> bool,
+) -> ExternResult<()> {
+    let kept_link_hash = index_links
+        .iter()
+        .filter(|l| l.tag.eq(&tag))
+        .max_by_key(|l| l.timestamp)
+        .filter(|kept| {
+            index_links.iter().chain(other_links.iter()).all(|l| {
+                l.create_link_hash.eq(&kept.create_link_hash)
+                    || l.timestamp < kept.timestamp
+                    || can_delete(l)
+            })
+        })
+        .map(|l| l.create_link_hash.clone());
+
+    for link in index_links.into_iter().chain(other_links.into_iter()) {
+        if kept_link_hash.as_ref() != Some(&link.create_link_hash) && can_delete(&link) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    if kept_link_hash.is_none() {
+        create_link(path.path_entry_hash()?, call_to_action_hash, link_type, tag)?;
+    }
+    Ok(())
+}
+
+/// Returns the links to all the calls to action, newest first
+#[hdk_extern]
+pub fn get_all_calls_to_action(pagination: Option<Pagination>) -> ExternResult<Vec<Link>> {
+    get_page_by_day(pagination, |day| {
+        let links = get_links(
+            all_calls_to_action_day_path(day).path_entry_hash()?,
+            LinkTypes::AllCallsToAction,
+            None,
+        )?;
+        Ok(sort_and_dedup(links))
+    })
+}
+
+/// Returns the links to the open calls to action, newest first
+///
+/// Calls to action that have expired but haven't been moved to the closed index yet are left out
+#[hdk_extern]
+pub fn get_open_calls_to_action(pagination: Option<Pagination>) -> ExternResult<Vec<Link>> {
+    let now = sys_time()?;
+    get_page_by_day(pagination, |day| {
+        Ok(get_latest_open_and_closed_links(day)?
+            .into_iter()
+            .filter(|(open, l)| *open && !is_expired(l, &now))
+            .map(|(_, l)| l)
+            .collect())
+    })
+}
+
+/// Returns the links to the closed calls to action, newest first
+///
+/// Calls to action that have expired but haven't been moved to the closed index yet are included
+#[hdk_extern]
+pub fn get_closed_calls_to_action(pagination: Option<Pagination>) -> ExternResult<Vec<Link>> {
+    let now = sys_time()?;
+    get_page_by_day(pagination, |day| {
+        Ok(get_latest_open_and_closed_links(day)?
+            .into_iter()
+            .filter(|(open, l)| !*open || is_expired(l, &now))
+            .map(|(_, l)| l)
+            .collect())
+    })
+}
+
+/// Returns the links to the calls to action with the given tag, newest first
+#[hdk_extern]
+pub fn get_calls_to_action_by_tag(tag: String) -> ExternResult<Vec<Link>> {
+    let links = get_links(
+        tag_path(&tag.trim().to_lowercase()).path_entry_hash()?,
+        LinkTypes::TagToCallsToAction,
+        None,
+    )?;
+    Ok(sort_and_dedup(links))
+}
+
+/// Maximum number of days that `get_calls_to_action_expiring_between` will scan
+pub const MAX_EXPIRATION_QUERY_DAYS: i64 = 366;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetCallsToActionExpiringBetweenInput {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+/// Returns the links to the open calls to action that expire between start and end, soonest first
+///
+/// Calls to action that have already been assembled or cancelled are left out
+#[hdk_extern]
+pub fn get_calls_to_action_expiring_between(
+    input: GetCallsToActionExpiringBetweenInput,
+) -> ExternResult<Vec<Link>> {
+    let start_day = expiration_day(&input.start);
+    let end_day = expiration_day(&input.end);
+    if end_day < start_day {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The end of the range must be after its start"
+        ))));
+    }
+    if end_day - start_day >= MAX_EXPIRATION_QUERY_DAYS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Can't query the expirations of more than {} days at once",
+            MAX_EXPIRATION_QUERY_DAYS
+        ))));
+    }
+
+    let mut links_with_expiration: Vec<(Timestamp, Link)> = vec![];
+    for day in start_day..=end_day {
+        let links = get_links(
+            expiration_day_path(day).path_entry_hash()?,
+            LinkTypes::ExpirationToCallsToAction,
+            None,
+        )?;
+        for link in links {
+            let Ok(tag) = ExpirationLinkTag::try_from(link.tag.clone()) else {
+                continue;
+            };
+            if tag.expiration_time < input.start || tag.expiration_time > input.end {
+                continue;
+            }
+            let Some(call_to_action_hash) = link.target.clone().into_action_hash() else {
+                continue;
+            };
+            if get_call_to_action_status(call_to_action_hash)?.eq(&CallToActionStatus::Open) {
+                links_with_expiration.push((tag.expiration_time, link));
+            }
+        }
+    }
+    links_with_expiration.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(dedup_targets(
+        links_with_expiration
+            .into_iter()
+            .map(|(_, link)| link)
+            .collect(),
+    ))
+}
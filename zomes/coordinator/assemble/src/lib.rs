@@ -1,15 +1,56 @@
+pub mod admin;
+pub mod approval;
 pub mod assembly;
 pub mod call_to_action;
+pub mod cancellations;
+pub mod children;
 pub mod commitment;
+pub mod countersigning;
+pub mod error;
+pub mod index;
+pub mod location;
+pub mod membrane;
+pub mod progress;
+pub mod properties;
 pub mod satisfaction;
+use approval::{get_commitment_approvals, is_commitment_rejected};
 use assemble_integrity::*;
-use call_to_action::get_latest_call_to_action;
-use commitment::{get_commitment, get_commitments_for_call_to_action};
+use assembly::get_assemblies_for_call_to_action;
+use call_to_action::{get_call_to_action_status, get_latest_call_to_action, CallToActionStatus};
+use cancellations::{get_cancellations_for, reconcile_satisfactions_for_commitment};
+use children::get_required_children_assemblies;
+use commitment::{amount_committed, get_uncancelled_commitments_for_need};
+use error::{call_local_zome, AssembleError};
 use hdk::prelude::*;
-use satisfaction::{get_latest_satisfaction, get_satisfactions_for_call_to_action};
+use index::update_call_to_action_index;
+use satisfaction::{
+    get_canonical_satisfactions_for_call_to_action, get_latest_satisfaction,
+    get_satisfactions_for_call_to_action, get_satisfactions_for_commitment,
+};
+use std::collections::{BTreeMap, BTreeSet};
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    // Admins signal their rejections to the committers,
+    // and the initiator of a countersigned assembly drives the session of the other signers
+    let zome_name = zome_info()?.name;
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_name.clone(), FunctionName::from("recv_remote_signal")));
+    functions.insert((
+        zome_name.clone(),
+        FunctionName::from("accept_assembly_countersigning"),
+    ));
+    functions.insert((
+        zome_name,
+        FunctionName::from("commit_countersigned_assembly"),
+    ));
+    create_cap_grant(CapGrantEntry {
+        tag: String::from("remote_calls"),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+
+    schedule("create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action")?;
     Ok(InitCallbackResult::Pass)
 }
 
@@ -22,96 +63,196 @@ pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
     }
 }
 
-// #[hdk_extern(infallible)]
-// fn create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action(
-//     _: Option<Schedule>,
-// ) -> Option<Schedule> {
-//     if let Err(err) = inner_create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action()
-//     {
-//         error!("Error trying to create satisfasctions or assemblies for my inconsisten calls to action: {:?}", err);
-//     }
-
-//     Some(Schedule::Persisted(String::from("1 * * * *")))
-// }
-
-// fn query_my_calls_to_action() -> ExternResult<Vec<Record>> {
-//     let filter = ChainQueryFilter::new().entry_type(UnitEntryTypes::CallToAction.try_into()?);
-//     let my_calls_to_action = query(filter)?;
-
-//     let mut my_calls_to_action_hashes: HashSet<ActionHash> = my_calls_to_action
-//         .into_iter()
-//         .map(|r| r.action_address().clone())
-//         .collect();
-
-//     let filter = ChainQueryFilter::new()
-//         .entry_type(UnitEntryTypes::Commitment.try_into()?)
-//         .include_entries(true);
-//     let my_commitments = query(filter)?;
-//     let calls_to_actions_from_my_commitments: Set<ActionHash> = my_commitments
-//         .into_iter()
-//         .filter_map(|r| r.entry().as_option())
-//         .filter_map(|e| Commitment::try_from(e).ok())
-//         .map(|c| c.call_to_action_hash)
-//         .collect();
-//     my_calls_to_action_hashes.append(calls_to_actions_from_my_commitments);
-
-//     let filter = ChainQueryFilter::new()
-//         .entry_type(UnitEntryTypes::Satisfaction.try_into()?)
-//         .include_entries(true);
-//     let my_satisfactions = query(filter)?;
-//     let calls_to_actions_from_my_satisfactions: Set<ActionHash> = my_satisfactions
-//         .into_iter()
-//         .filter_map(|r| r.entry().as_option())
-//         .filter_map(|e| Satisfaction::try_from(e).ok())
-//         .map(|c| c.call_to_action_hash)
-//         .collect();
-//     my_calls_to_action_hashes.append(calls_to_actions_from_my_satisfactions);
-
-//     let filter = ChainQueryFilter::new()
-//         .entry_type(UnitEntryTypes::Assembly.try_into()?)
-//         .include_entries(true);
-//     let my_assemblies = query(filter)?;
-//     let calls_to_actions_from_my_assemblies: Set<ActionHash> = my_assemblies
-//         .into_iter()
-//         .filter_map(|r| r.entry().as_option())
-//         .filter_map(|e| Satisfaction::try_from(e).ok())
-//         .map(|c| c.call_to_action_hash)
-//         .collect();
-//     my_calls_to_action_hashes.append(calls_to_actions_from_my_assemblies);
-
-//     Ok(my_calls_to_action)
-// }
-
-// fn inner_create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action() -> ExternResult<()>
-// {
-//     Ok(())
-// }
-
-// fn check_if_need_is_satisfied_with_commitments(
-//     commitments_hashes: Vec<ActionHash>,
-// ) -> ExternResult<()> {
-// }
-
-fn check_if_need_is_satisfied_with_new_commitment(
-    commitment_hash: ActionHash,
-    commitment: Commitment,
+/// Creates the satisfactions or assemblies that a failed or concurrent `post_commit` didn't create
+#[hdk_extern(infallible)]
+fn create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action(
+    _: Option<Schedule>,
+) -> Option<Schedule> {
+    if let Err(err) = inner_create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action()
+    {
+        error!("Error trying to create satisfactions or assemblies for my inconsistent calls to action: {:?}", err);
+    }
+
+    Some(Schedule::Persisted(String::from(REPAIR_SCHEDULE)))
+}
+
+/// Every hour: post_commit already creates the satisfactions and assemblies, this only repairs what it missed
+const REPAIR_SCHEDULE: &str = "0 0 * * * *";
+
+/// Returns the calls to action that I have created, committed to or satisfied, and that I haven't assembled
+fn query_my_calls_to_action() -> ExternResult<BTreeSet<ActionHash>> {
+    let filter = ChainQueryFilter::new().entry_type(UnitEntryTypes::CallToAction.try_into()?);
+    let my_calls_to_action = query(filter)?;
+
+    let mut my_calls_to_action_hashes: BTreeSet<ActionHash> = my_calls_to_action
+        .into_iter()
+        .filter(|r| matches!(r.action(), Action::Create(_)))
+        .map(|r| r.action_address().clone())
+        .collect();
+
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Commitment.try_into()?)
+        .include_entries(true);
+    let my_commitments = query(filter)?;
+    my_calls_to_action_hashes.extend(
+        my_commitments
+            .into_iter()
+            .filter_map(|r| Commitment::try_from(r).ok())
+            .map(|c| c.call_to_action_hash),
+    );
+
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Satisfaction.try_into()?)
+        .include_entries(true);
+    let my_satisfactions = query(filter)?;
+    my_calls_to_action_hashes.extend(
+        my_satisfactions
+            .into_iter()
+            .filter_map(|r| Satisfaction::try_from(r).ok())
+            .map(|s| s.call_to_action_hash),
+    );
+
+    // The calls to action that I assembled don't need repairing, unless I invalidated their assembly
+    let filter = ChainQueryFilter::new().action_type(ActionType::Delete);
+    let my_deleted_hashes: BTreeSet<ActionHash> = query(filter)?
+        .into_iter()
+        .filter_map(|r| match r.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Assembly.try_into()?)
+        .include_entries(true);
+    let my_assemblies = query(filter)?;
+    for record in my_assemblies {
+        let deleted = my_deleted_hashes.contains(record.action_address());
+        if let Ok(assembly) = Assembly::try_from(record) {
+            if !deleted {
+                my_calls_to_action_hashes.remove(&assembly.call_to_action_hash);
+            }
+        }
+    }
+
+    Ok(my_calls_to_action_hashes)
+}
+
+fn inner_create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action() -> ExternResult<()>
+{
+    reconcile_my_cancelled_commitments()?;
+    for call_to_action_hash in query_my_calls_to_action()? {
+        if let Err(err) = repair_call_to_action(call_to_action_hash.clone()) {
+            error!(
+                "Error trying to repair call to action {}: {:?}",
+                call_to_action_hash, err
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Deletes the satisfactions, and through them the assemblies, that rely on my commitments
+/// that were cancelled without going through `cancel_commitment`
+///
+/// Uncancelled commitments satisfy their need again when their call to action gets repaired
+fn reconcile_my_cancelled_commitments() -> ExternResult<()> {
+    let filter = ChainQueryFilter::new().entry_type(UnitEntryTypes::Commitment.try_into()?);
+    for record in query(filter)? {
+        let commitment_hash = record.action_address().clone();
+        // Satisfactions are unlinked from their commitments when deleted, so this only checks the ones still in use
+        if get_satisfactions_for_commitment(commitment_hash.clone())?.is_empty() {
+            continue;
+        }
+        if get_cancellations_for(commitment_hash.clone())?.is_empty() {
+            continue;
+        }
+        if let Err(err) = reconcile_satisfactions_for_commitment(commitment_hash.clone()) {
+            error!(
+                "Error trying to reconcile the satisfactions for commitment {}: {:?}",
+                commitment_hash, err
+            );
+        }
+    }
+    Ok(())
+}
+
+fn repair_call_to_action(call_to_action_hash: ActionHash) -> ExternResult<()> {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+
+    // The previous run already moved the calls to action that had expired by then to the closed index
+    if let Some(expiration_time) = call_to_action.expiration_time {
+        let an_hour_ago = Timestamp::from_micros(sys_time()?.as_micros() - 60 * 60 * 1_000_000);
+        if expiration_time < an_hour_ago {
+            return Ok(());
+        }
+    }
+
+    // Calls to action move to the closed index when they expire,
+    // and back to the open one when their assembly is invalidated
+    let status = get_call_to_action_status(call_to_action_hash.clone())?;
+    update_call_to_action_index(call_to_action_hash.clone(), &call_to_action, &status)?;
+
+    if status != CallToActionStatus::Open {
+        return Ok(());
+    }
+
+    for need_index in 0..call_to_action.needs.len() {
+        check_if_need_is_satisfied(call_to_action_hash.clone(), need_index as u32, None)?;
+    }
+
+    check_if_call_to_action_is_fulfilled(call_to_action_hash, None)
+}
+
+/// Creates a satisfaction for the need if its uncancelled commitments reach its min_necessary,
+/// or if they contribute anything at all to an optional need that the fulfillment policy counts
+///
+/// Needs that require admin approval are only satisfied by an admin, with the approved commitments
+///
+/// The newly created commitment is passed along since it might not be linked from the call to action yet
+fn check_if_need_is_satisfied(
+    call_to_action_hash: ActionHash,
+    need_index: u32,
+    new_commitment: Option<(ActionHash, CommitmentLinkTag)>,
 ) -> ExternResult<()> {
-    let call_to_action_record = get_latest_call_to_action(commitment.call_to_action_hash.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not find call to action for this commitment".into()
-        )))?;
-    let call_to_action = CallToAction::try_from(call_to_action_record.entry().as_option().ok_or(
-        wasm_error!(WasmErrorInner::Guest(
-            "CallToAction record has no entry".into()
-        )),
-    )?)?;
-
-    if call_to_action.needs[commitment.need_index as usize].requires_admin_approval {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action_version_hash = call_to_action_record.action_address().clone();
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+
+    let need =
+        call_to_action
+            .needs
+            .get(need_index as usize)
+            .ok_or(AssembleError::NeedNotFound {
+                call_to_action_hash: call_to_action_hash.clone(),
+                need_index,
+            })?;
+    if !does_fulfillment_policy_count_need(&call_to_action, need_index) {
         return Ok(());
     }
 
-    let satisfaction_hashes =
-        get_satisfactions_for_call_to_action(commitment.call_to_action_hash.clone())?;
+    // Only the admins can satisfy the needs that require their approval, and they can't change
+    // while a need requires it, so validation reads the same admins from the version the satisfaction references
+    let mut approval_threshold = None;
+    if need.requires_admin_approval {
+        let original_record = get(call_to_action_hash.clone(), GetOptions::default())?.ok_or(
+            AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+        )?;
+        if !is_author_or_admin(
+            original_record.action().author(),
+            &call_to_action,
+            &agent_info()?.agent_latest_pubkey,
+        ) {
+            return Ok(());
+        }
+        approval_threshold = Some(need.approval_threshold);
+    }
+
+    let satisfaction_hashes = get_satisfactions_for_call_to_action(call_to_action_hash.clone())?;
     let need_is_already_satisfied = satisfaction_hashes
         .clone()
         .into_iter()
@@ -122,151 +263,177 @@ fn check_if_need_is_satisfied_with_new_commitment(
         .map(|record| Satisfaction::try_from(record))
         .collect::<ExternResult<Vec<Satisfaction>>>()?
         .into_iter()
-        .find(|s| s.need_index == commitment.need_index);
+        .find(|s| s.need_index == need_index);
 
     if need_is_already_satisfied.is_some() {
         return Ok(());
     }
 
-    let commitments_links = get_commitments_for_call_to_action(commitment.call_to_action_hash)?;
-    let commitments_hashes: Vec<ActionHash> = commitments_links
-        .into_iter()
-        .filter_map(|l| l.target.into_action_hash())
-        .collect();
-    let mut set: HashSet<ActionHash> = HashSet::from_iter(commitments_hashes);
-    set.insert(commitment_hash);
-
-    let my_pub_key = agent_info()?.agent_initial_pubkey;
+    let mut commitments =
+        get_uncancelled_commitments_for_need(call_to_action_hash.clone(), need_index)?;
+    if let Some((commitment_hash, commitment)) = new_commitment {
+        if !commitments
+            .iter()
+            .any(|(hash, _)| hash.eq(&commitment_hash))
+        {
+            commitments.push((commitment_hash, commitment));
+        }
+    }
 
-    let commitments_hashes: Vec<ActionHash> = set.into_iter().collect();
-    let amount_contributed = commitments_hashes
-        .clone()
-        .into_iter()
-        .map(|hash| {
-            let response = call_remote(
-                my_pub_key.clone(),
-                "cancellations",
-                FunctionName::from("get_cancellations_for"),
-                None,
-                hash.clone(),
-            )?;
-            match response {
-                ZomeCallResponse::Ok(result) => {
-                    let hashes: Vec<ActionHash> =
-                        result.decode().map_err(|err| wasm_error!(err))?;
-                    Ok((hash, hashes))
-                }
-                _ => Err(wasm_error!(WasmErrorInner::Guest(format!(
-                    "Error getting the cancellations for commitment: {:?}",
-                    response
-                )))),
+    let mut approvals_hashes: Vec<ActionHash> = vec![];
+    if let Some(approval_threshold) = approval_threshold {
+        let mut approved_commitments = vec![];
+        for (commitment_hash, commitment) in commitments {
+            let approvals = get_commitment_approvals(commitment_hash.clone())?;
+            if approvals.len() as u32 >= approval_threshold
+                && !is_commitment_rejected(commitment_hash.clone())?
+            {
+                approvals_hashes.extend(approvals.into_values());
+                approved_commitments.push((commitment_hash, commitment));
             }
-        })
-        .collect::<ExternResult<Vec<(ActionHash, Vec<ActionHash>)>>>()?
-        .into_iter()
-        .filter(|(_h, cancellations)| cancellations.len() == 0)
-        .map(|(hash, _)| get_commitment(hash))
-        .collect::<ExternResult<Vec<Option<Record>>>>()?
-        .into_iter()
-        .filter_map(|c| c)
-        .map(|record| Commitment::try_from(record))
-        .collect::<ExternResult<Vec<Commitment>>>()?
-        .into_iter()
-        .filter(|c| c.need_index == commitment.need_index)
-        .fold(0, |acc, next| acc + next.amount);
-
-    let min_necessary = call_to_action.needs[commitment.need_index as usize].min_necessary;
-
-    if min_necessary > 0 && amount_contributed >= min_necessary {
-        let my_pub_key = agent_info()?.agent_latest_pubkey;
-        let result = call_remote(
-            my_pub_key,
+        }
+        commitments = approved_commitments;
+    }
+    approvals_hashes.sort();
+
+    let amount_contributed = amount_committed(&commitments);
+    let mut commitments_hashes: Vec<ActionHash> =
+        commitments.into_iter().map(|(hash, _)| hash).collect();
+    commitments_hashes.sort();
+
+    if amount_contributed >= need.amount_to_satisfy() {
+        let satisfaction = Satisfaction {
+            call_to_action_hash: call_to_action_hash.clone(),
+            need_index,
+            commitments_hashes,
+            approvals_hashes,
+            call_to_action_version_hash: Some(call_to_action_version_hash),
+        };
+        // This also runs from post_commit, which can't write to the source chain itself
+        let record: Record = call_local_zome(
             zome_info()?.name,
-            FunctionName::from("create_satisfaction"),
-            None,
-            Satisfaction {
-                call_to_action_hash: call_to_action_record.signed_action.hashed.hash.clone(),
-                need_index: commitment.need_index,
-                commitments_hashes,
-            },
+            "create_satisfaction",
+            satisfaction.clone(),
+        )?;
+        check_if_call_to_action_is_fulfilled(
+            call_to_action_hash,
+            Some((record.action_address().clone(), satisfaction)),
         )?;
-        match result {
-            ZomeCallResponse::Ok(_) => Ok(()),
-            _ => Err(wasm_error!(WasmErrorInner::Guest(format!(
-                "Error creating the satisfaction {:?}",
-                result
-            )))),
-        }?;
     }
 
     Ok(())
 }
 
+/// Creates an assembly for the call to action if its satisfied needs meet its fulfillment policy
+/// and all the children required by its children policy have assembled
+///
+/// Assemblies that must be countersigned are left to the author or an admin to start with `create_countersigned_assembly`
+///
+/// The newly created satisfaction is passed along since it might not be linked from the call to action yet
 fn check_if_call_to_action_is_fulfilled(
-    action_hash: ActionHash,
-    satisfaction: Satisfaction,
+    call_to_action_hash: ActionHash,
+    new_satisfaction: Option<(ActionHash, Satisfaction)>,
 ) -> ExternResult<()> {
-    let call_to_action_record =
-        get_latest_call_to_action(satisfaction.call_to_action_hash.clone())?.ok_or(wasm_error!(
-            WasmErrorInner::Guest("Could not find call to action for this satisfaction".into())
-        ))?;
-    let call_to_action = CallToAction::try_from(call_to_action_record.entry().as_option().ok_or(
-        wasm_error!(WasmErrorInner::Guest(
-            "CallToAction record has no entry".into()
-        )),
-    )?)?;
+    if !get_assemblies_for_call_to_action(call_to_action_hash.clone())?.is_empty() {
+        return Ok(());
+    }
+
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action_version_hash = call_to_action_record.action_address().clone();
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    if call_to_action.countersigned_assembly {
+        return Ok(());
+    }
+
+    if let Some(assembly) = build_assembly_if_fulfilled(
+        call_to_action_hash,
+        call_to_action_version_hash,
+        &call_to_action,
+        new_satisfaction,
+    )? {
+        // This also runs from post_commit, which can't write to the source chain itself
+        let _: Record = call_local_zome(zome_info()?.name, "create_assembly", assembly)?;
+    }
+
+    Ok(())
+}
 
+/// Builds the assembly for the call to action with the canonical satisfaction for each need,
+/// if they meet its fulfillment policy and all the children required by its children policy have assembled
+///
+/// The policies are the ones of the given version of the call to action, which the assembly references
+pub fn build_assembly_if_fulfilled(
+    call_to_action_hash: ActionHash,
+    call_to_action_version_hash: ActionHash,
+    call_to_action: &CallToAction,
+    new_satisfaction: Option<(ActionHash, Satisfaction)>,
+) -> ExternResult<Option<Assembly>> {
+    // Only the canonical satisfaction for each need is part of the assembly
     let satisfactions_links =
-        get_satisfactions_for_call_to_action(satisfaction.call_to_action_hash)?;
-    let satisfactions_hashes: Vec<ActionHash> = satisfactions_links
+        get_canonical_satisfactions_for_call_to_action(call_to_action_hash.clone())?;
+
+    let mut satisfactions_by_need: BTreeMap<u32, ActionHash> = BTreeMap::new();
+    for satisfaction_hash in satisfactions_links
         .into_iter()
         .filter_map(|l| l.target.into_action_hash())
-        .collect();
+    {
+        let record = get_latest_satisfaction(satisfaction_hash.clone())?;
+        let other_satisfaction = Satisfaction::try_from(record)?;
+        satisfactions_by_need.insert(other_satisfaction.need_index, satisfaction_hash);
+    }
+    if let Some((action_hash, satisfaction)) = new_satisfaction {
+        match satisfactions_by_need.get(&satisfaction.need_index) {
+            Some(canonical_hash) if canonical_hash <= &action_hash => {}
+            _ => {
+                satisfactions_by_need.insert(satisfaction.need_index, action_hash);
+            }
+        }
+    }
 
-    let mut set: HashSet<ActionHash> = HashSet::from_iter(satisfactions_hashes);
-    set.insert(action_hash);
-    let satisfactions_hashes: Vec<ActionHash> = set.into_iter().collect();
+    let satisfied_needs: BTreeSet<u32> = satisfactions_by_need.keys().cloned().collect();
 
-    let satisfactions = satisfactions_hashes
-        .clone()
-        .into_iter()
-        .map(|hash| get_latest_satisfaction(hash))
-        .collect::<ExternResult<Vec<Record>>>()?
-        .into_iter()
-        .map(|record| Satisfaction::try_from(record))
-        .collect::<ExternResult<Vec<Satisfaction>>>()?;
-
-    let are_all_needs_satisfied = (0..call_to_action.needs.len())
-        .into_iter()
-        .all(|need_index| {
-            call_to_action.needs[need_index].min_necessary == 0
-                || satisfactions
-                    .iter()
-                    .find(|s| s.need_index == need_index as u32)
-                    .is_some()
-        });
-
-    if are_all_needs_satisfied {
-        let my_pub_key = agent_info()?.agent_latest_pubkey;
-        let result = call_remote(
-            my_pub_key,
-            zome_info()?.name,
-            FunctionName::from("create_assembly"),
-            None,
-            Assembly {
-                call_to_action_hash: call_to_action_record.signed_action.hashed.hash.clone(),
-                satisfactions_hashes,
-            },
-        )?;
-        match result {
-            ZomeCallResponse::Ok(_) => Ok(()),
-            _ => Err(wasm_error!(WasmErrorInner::Guest(format!(
-                "Error creating the assembly {:?}",
-                result
-            )))),
-        }?;
+    if !is_fulfillment_policy_met(call_to_action, &satisfied_needs) {
+        return Ok(None);
     }
 
+    let Some(children_assemblies_hashes) =
+        get_required_children_assemblies(call_to_action_hash.clone(), call_to_action)?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(Assembly {
+        call_to_action_hash,
+        satisfactions_hashes: satisfactions_by_need.into_values().collect(),
+        children_assemblies_hashes,
+        call_to_action_version_hash: Some(call_to_action_version_hash),
+    }))
+}
+
+fn check_if_approved_commitment_satisfies_its_need(
+    commitment_approval: CommitmentApproval,
+) -> ExternResult<()> {
+    let commitment_record = get(commitment_approval.commitment_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the approved Commitment"
+        ))))?;
+    let commitment = Commitment::try_from(commitment_record)?;
+    check_if_need_is_satisfied(commitment.call_to_action_hash, commitment.need_index, None)
+}
+
+fn check_if_parent_is_fulfilled(call_to_action_hash: ActionHash) -> ExternResult<()> {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?
+        .ok_or(AssembleError::CallToActionNotFound(call_to_action_hash))?;
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+    if let Some(parent_call_to_action_hash) = call_to_action.parent_call_to_action_hash {
+        if get_call_to_action_status(parent_call_to_action_hash.clone())?
+            .eq(&CallToActionStatus::Open)
+        {
+            check_if_call_to_action_is_fulfilled(parent_call_to_action_hash, None)?;
+        }
+    }
     Ok(())
 }
 
@@ -295,6 +462,48 @@ pub enum Signal {
         action: SignedActionHashed,
         original_app_entry: EntryTypes,
     },
+    CommitmentRejected {
+        commitment_rejection_hash: ActionHash,
+        commitment_rejection: CommitmentRejection,
+    },
+}
+
+/// Receives the rejections of our commitments from the admins that rejected them
+///
+/// Anyone can call this function, so the rejection is only signalled if it was committed
+/// by the caller for one of our commitments, since validation already checked that its author is an admin
+#[hdk_extern]
+pub fn recv_remote_signal(signal: Signal) -> ExternResult<()> {
+    let Signal::CommitmentRejected {
+        commitment_rejection_hash,
+        commitment_rejection,
+    } = &signal
+    else {
+        return Ok(());
+    };
+    let Some(record) = get(commitment_rejection_hash.clone(), GetOptions::default())? else {
+        return Ok(());
+    };
+    if record.action().author().ne(&call_info()?.provenance)
+        || CommitmentRejection::try_from(record)?.ne(commitment_rejection)
+    {
+        return Ok(());
+    }
+    let Some(commitment_record) = get(
+        commitment_rejection.commitment_hash.clone(),
+        GetOptions::default(),
+    )?
+    else {
+        return Ok(());
+    };
+    if commitment_record
+        .action()
+        .author()
+        .eq(&agent_info()?.agent_latest_pubkey)
+    {
+        emit_signal(signal)?;
+    }
+    Ok(())
 }
 
 fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
@@ -337,17 +546,21 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
             if let Ok(Some(app_entry)) = get_entry_for_action(&action.hashed.hash) {
                 match app_entry.clone() {
                     EntryTypes::Commitment(commitment) => {
-                        if let Err(err) = check_if_need_is_satisfied_with_new_commitment(
-                            action.hashed.hash.clone(),
-                            commitment,
+                        if let Err(err) = check_if_need_is_satisfied(
+                            commitment.call_to_action_hash.clone(),
+                            commitment.need_index,
+                            Some((
+                                action.hashed.hash.clone(),
+                                CommitmentLinkTag::new(&commitment, action.hashed.author().clone()),
+                            )),
                         ) {
                             error!("Error trying to satisfy a need {:?}", err);
                         }
                     }
                     EntryTypes::Satisfaction(satisfaction) => {
                         if let Err(err) = check_if_call_to_action_is_fulfilled(
-                            action.hashed.hash.clone(),
-                            satisfaction,
+                            satisfaction.call_to_action_hash.clone(),
+                            Some((action.hashed.hash.clone(), satisfaction)),
                         ) {
                             error!(
                                 "Error trying to create an assembly from a call to action {:?}",
@@ -355,6 +568,34 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
                             );
                         }
                     }
+                    EntryTypes::CommitmentApproval(commitment_approval) => {
+                        if let Err(err) =
+                            check_if_approved_commitment_satisfies_its_need(commitment_approval)
+                        {
+                            error!("Error trying to satisfy a need {:?}", err);
+                        }
+                    }
+                    EntryTypes::Assembly(assembly) => {
+                        // Links can't be created during the countersigning session, so the initiator creates them now
+                        let result: ExternResult<()> = zome_info().and_then(|zome_info| {
+                            call_local_zome(
+                                zome_info.name,
+                                "index_countersigned_assembly",
+                                action.hashed.hash.clone(),
+                            )
+                        });
+                        if let Err(err) = result {
+                            error!("Error trying to index a countersigned assembly {:?}", err);
+                        }
+                        // The parent might have been waiting for this child to assemble
+                        if let Err(err) = check_if_parent_is_fulfilled(assembly.call_to_action_hash)
+                        {
+                            error!(
+                                "Error trying to create an assembly from a parent call to action {:?}",
+                                err
+                            );
+                        }
+                    }
                     _ => {}
                 }
 
@@ -0,0 +1,252 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+use crate::index::{get_original_record, index_links_deleter};
+
+/// Queries never scan more geohash cells than this, picking a coarser precision instead
+const MAX_GEOHASH_CELLS: usize = 16;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadiusQuery {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+}
+
+fn geohash_links(call_to_action_hash: &ActionHash, location: &Location) -> ExternResult<Vec<Link>> {
+    let hash = geohash(location.latitude, location.longitude, GEOHASH_PRECISION);
+    let target = AnyLinkableHash::from(call_to_action_hash.clone());
+    let mut links: Vec<Link> = vec![];
+    for precision in 1..=GEOHASH_PRECISION {
+        links.extend(
+            get_links(
+                geohash_path(&hash[..precision]).path_entry_hash()?,
+                LinkTypes::GeohashToCallsToAction,
+                None,
+            )?
+            .into_iter()
+            .filter(|l| l.target.eq(&target)),
+        );
+    }
+    Ok(links)
+}
+
+/// Links the call to action from the paths of all the prefixes of the geohash of its location
+pub fn create_location_links(
+    call_to_action_hash: ActionHash,
+    call_to_action_version_hash: ActionHash,
+    location: &Location,
+) -> ExternResult<()> {
+    let hash = geohash(location.latitude, location.longitude, GEOHASH_PRECISION);
+    for precision in 1..=GEOHASH_PRECISION {
+        create_link(
+            geohash_path(&hash[..precision]).path_entry_hash()?,
+            call_to_action_hash.clone(),
+            LinkTypes::GeohashToCallsToAction,
+            LinkTag::try_from(LocationLinkTag {
+                latitude: location.latitude,
+                longitude: location.longitude,
+                call_to_action_version_hash: call_to_action_version_hash.clone(),
+            })?,
+        )?;
+    }
+    Ok(())
+}
+
+/// Moves the call to action to the geohash paths of its new location
+pub fn update_location_index(
+    call_to_action_hash: ActionHash,
+    updated_call_to_action_hash: ActionHash,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> ExternResult<()> {
+    if previous_call_to_action
+        .location
+        .eq(&updated_call_to_action.location)
+    {
+        return Ok(());
+    }
+    if let Some(location) = &previous_call_to_action.location {
+        let can_delete = index_links_deleter(get_original_record(&call_to_action_hash)?)?;
+        for link in geohash_links(&call_to_action_hash, location)? {
+            if can_delete(&link) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    if let Some(location) = &updated_call_to_action.location {
+        create_location_links(call_to_action_hash, updated_call_to_action_hash, location)?;
+    }
+    Ok(())
+}
+
+/// Returns the geohashes of the cells of the given precision that cover the bounding box
+fn covering_geohashes(bounding_box: &BoundingBox, precision: usize) -> Vec<String> {
+    let longitude_bits = (5 * precision + 1) / 2;
+    let latitude_bits = 5 * precision / 2;
+    let cell_height = 180.0 / (1u64 << latitude_bits) as f64;
+    let cell_width = 360.0 / (1u64 << longitude_bits) as f64;
+    let max_latitude_cell = (1u64 << latitude_bits) - 1;
+    let max_longitude_cell = (1u64 << longitude_bits) - 1;
+
+    let latitude_cell =
+        |latitude: f64| (((latitude + 90.0) / cell_height).floor() as u64).min(max_latitude_cell);
+    let longitude_cell = |longitude: f64| {
+        (((longitude + 180.0) / cell_width).floor() as u64).min(max_longitude_cell)
+    };
+
+    let mut geohashes: Vec<String> = vec![];
+    for i in latitude_cell(bounding_box.min_latitude)..=latitude_cell(bounding_box.max_latitude) {
+        for j in
+            longitude_cell(bounding_box.min_longitude)..=longitude_cell(bounding_box.max_longitude)
+        {
+            geohashes.push(geohash(
+                -90.0 + (i as f64 + 0.5) * cell_height,
+                -180.0 + (j as f64 + 0.5) * cell_width,
+                precision,
+            ));
+        }
+    }
+    geohashes
+}
+
+fn validate_bounding_box(bounding_box: &BoundingBox) -> ExternResult<()> {
+    for (latitude, longitude) in [
+        (bounding_box.min_latitude, bounding_box.min_longitude),
+        (bounding_box.max_latitude, bounding_box.max_longitude),
+    ] {
+        if let ValidateCallbackResult::Invalid(reason) = validate_location(&Location {
+            latitude,
+            longitude,
+            place_name: String::new(),
+        }) {
+            return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+        }
+    }
+    if bounding_box.min_latitude > bounding_box.max_latitude
+        || bounding_box.min_longitude > bounding_box.max_longitude
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The minimum coordinates of a bounding box can't be greater than its maximum ones"
+        ))));
+    }
+    Ok(())
+}
+
+/// Returns the links to the calls to action in the bounding box, along with their coordinates
+fn get_located_links_in_bounding_box(
+    bounding_box: &BoundingBox,
+) -> ExternResult<Vec<(LocationLinkTag, Link)>> {
+    validate_bounding_box(bounding_box)?;
+
+    let mut geohashes = covering_geohashes(bounding_box, 1);
+    for precision in 2..=GEOHASH_PRECISION {
+        let finer_geohashes = covering_geohashes(bounding_box, precision);
+        if finer_geohashes.len() > MAX_GEOHASH_CELLS {
+            break;
+        }
+        geohashes = finer_geohashes;
+    }
+
+    let mut seen: Vec<AnyLinkableHash> = vec![];
+    let mut located_links: Vec<(LocationLinkTag, Link)> = vec![];
+    for hash in geohashes {
+        let links = get_links(
+            geohash_path(&hash).path_entry_hash()?,
+            LinkTypes::GeohashToCallsToAction,
+            None,
+        )?;
+        for link in links {
+            let Ok(tag) = LocationLinkTag::try_from(link.tag.clone()) else {
+                continue;
+            };
+            let is_inside = tag.latitude >= bounding_box.min_latitude
+                && tag.latitude <= bounding_box.max_latitude
+                && tag.longitude >= bounding_box.min_longitude
+                && tag.longitude <= bounding_box.max_longitude;
+            if is_inside && !seen.contains(&link.target) {
+                seen.push(link.target.clone());
+                located_links.push((tag, link));
+            }
+        }
+    }
+    Ok(located_links)
+}
+
+#[hdk_extern]
+pub fn get_calls_to_action_in_bounding_box(bounding_box: BoundingBox) -> ExternResult<Vec<Link>> {
+    Ok(get_located_links_in_bounding_box(&bounding_box)?
+        .into_iter()
+        .map(|(_, link)| link)
+        .collect())
+}
+
+/// Great-circle distance between two coordinates, in kilometers
+fn haversine_distance_km(
+    latitude_1: f64,
+    longitude_1: f64,
+    latitude_2: f64,
+    longitude_2: f64,
+) -> f64 {
+    let delta_latitude = (latitude_2 - latitude_1).to_radians();
+    let delta_longitude = (longitude_2 - longitude_1).to_radians();
+    let a = (delta_latitude / 2.0).sin().powi(2)
+        + latitude_1.to_radians().cos()
+            * latitude_2.to_radians().cos()
+            * (delta_longitude / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Returns the links to the calls to action within the radius, closest first
+///
+/// The bounding box of the radius is clamped to the valid coordinates,
+/// so results don't wrap around the antimeridian
+#[hdk_extern]
+pub fn get_calls_to_action_within_radius(query: RadiusQuery) -> ExternResult<Vec<Link>> {
+    if !query.radius_km.is_finite() || query.radius_km < 0.0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The radius must be a positive number of kilometers"
+        ))));
+    }
+    let latitude_delta = (query.radius_km / EARTH_RADIUS_KM).to_degrees();
+    let longitude_delta = latitude_delta / query.latitude.to_radians().cos().max(0.01);
+    let bounding_box = BoundingBox {
+        min_latitude: (query.latitude - latitude_delta).max(-90.0),
+        min_longitude: (query.longitude - longitude_delta).max(-180.0),
+        max_latitude: (query.latitude + latitude_delta).min(90.0),
+        max_longitude: (query.longitude + longitude_delta).min(180.0),
+    };
+
+    let mut links_with_distance: Vec<(f64, Link)> =
+        get_located_links_in_bounding_box(&bounding_box)?
+            .into_iter()
+            .map(|(tag, link)| {
+                (
+                    haversine_distance_km(
+                        query.latitude,
+                        query.longitude,
+                        tag.latitude,
+                        tag.longitude,
+                    ),
+                    link,
+                )
+            })
+            .filter(|(distance, _)| *distance <= query.radius_km)
+            .collect();
+    links_with_distance.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    Ok(links_with_distance
+        .into_iter()
+        .map(|(_, link)| link)
+        .collect())
+}
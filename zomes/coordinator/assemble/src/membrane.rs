@@ -0,0 +1,49 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+/// Creates the membrane proof that the invitee needs to join this DNA, extending my own chain of invitations
+#[hdk_extern]
+pub fn create_invitation(invitee: AgentPubKey) -> ExternResult<SerializedBytes> {
+    let Some(progenitor) = DnaProperties::get()?.progenitor() else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "This DNA doesn't have a progenitor, so every agent can join it without an invitation"
+        ))));
+    };
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+
+    let mut invitations = if progenitor.eq(&my_pub_key) {
+        vec![]
+    } else {
+        get_my_membrane_proof()?.invitations
+    };
+
+    let invitation = Invitation {
+        dna_hash: dna_info()?.hash,
+        invitee,
+    };
+    let signature = sign(my_pub_key.clone(), &invitation)?;
+    invitations.push(SignedInvitation {
+        invitation,
+        inviter: my_pub_key,
+        signature,
+    });
+
+    SerializedBytes::try_from(AssembleMembraneProof { invitations }).map_err(|err| wasm_error!(err))
+}
+
+/// The membrane proof that I joined this DNA with
+fn get_my_membrane_proof() -> ExternResult<AssembleMembraneProof> {
+    let records = query(ChainQueryFilter::new().action_type(ActionType::AgentValidationPkg))?;
+    for record in records {
+        if let Action::AgentValidationPkg(AgentValidationPkg {
+            membrane_proof: Some(membrane_proof),
+            ..
+        }) = record.action()
+        {
+            return AssembleMembraneProof::try_from(membrane_proof.clone());
+        }
+    }
+    Err(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the membrane proof that I joined with"
+    ))))
+}
@@ -0,0 +1,88 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::assembly::get_canonical_assembly_for_call_to_action;
+use crate::call_to_action::get_latest_call_to_action;
+use crate::commitment::{amount_committed, get_uncancelled_commitments_for_call_to_action};
+use crate::error::AssembleError;
+use crate::satisfaction::{
+    get_canonical_satisfactions_for_call_to_action, get_latest_satisfaction,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NeedProgress {
+    pub need_index: u32,
+    pub amount_contributed: u32,
+    pub committers_count: u32,
+    pub satisfied: bool,
+    pub satisfaction_hash: Option<ActionHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CallToActionProgress {
+    pub needs: Vec<NeedProgress>,
+    pub assembled: bool,
+    pub assembly_hash: Option<ActionHash>,
+}
+
+#[hdk_extern]
+pub fn get_call_to_action_progress(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<CallToActionProgress> {
+    let call_to_action_record = get_latest_call_to_action(call_to_action_hash.clone())?.ok_or(
+        AssembleError::CallToActionNotFound(call_to_action_hash.clone()),
+    )?;
+    let call_to_action = CallToAction::try_from(call_to_action_record)?;
+
+    let mut needs: Vec<NeedProgress> = call_to_action
+        .needs
+        .iter()
+        .enumerate()
+        .map(|(need_index, need)| NeedProgress {
+            need_index: need_index as u32,
+            amount_contributed: 0,
+            committers_count: 0,
+            satisfied: need.min_necessary == 0,
+            satisfaction_hash: None,
+        })
+        .collect();
+
+    let mut commitments: Vec<Vec<(ActionHash, CommitmentLinkTag)>> = vec![vec![]; needs.len()];
+    for (commitment_hash, tag) in
+        get_uncancelled_commitments_for_call_to_action(call_to_action_hash.clone())?
+    {
+        if let Some(need_commitments) = commitments.get_mut(tag.need_index as usize) {
+            need_commitments.push((commitment_hash, tag));
+        }
+    }
+    for (need_progress, need_commitments) in needs.iter_mut().zip(commitments) {
+        need_progress.amount_contributed = amount_committed(&need_commitments);
+        need_progress.committers_count = need_commitments
+            .into_iter()
+            .map(|(_, tag)| tag.committer)
+            .collect::<BTreeSet<AgentPubKey>>()
+            .len() as u32;
+    }
+
+    for link in get_canonical_satisfactions_for_call_to_action(call_to_action_hash.clone())? {
+        let Some(satisfaction_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let satisfaction =
+            Satisfaction::try_from(get_latest_satisfaction(satisfaction_hash.clone())?)?;
+        if let Some(need_progress) = needs.get_mut(satisfaction.need_index as usize) {
+            need_progress.satisfied = true;
+            need_progress.satisfaction_hash = Some(satisfaction_hash);
+        }
+    }
+
+    let assembly_hash = get_canonical_assembly_for_call_to_action(call_to_action_hash)?
+        .and_then(|l| l.target.into_action_hash());
+
+    Ok(CallToActionProgress {
+        needs,
+        assembled: assembly_hash.is_some(),
+        assembly_hash,
+    })
+}
@@ -0,0 +1,8 @@
+use assemble_integrity::*;
+use hdk::prelude::*;
+
+/// The policies of this community, so that the UI can follow them and find the zomes it depends on
+#[hdk_extern]
+pub fn get_dna_properties(_: ()) -> ExternResult<DnaProperties> {
+    DnaProperties::get()
+}
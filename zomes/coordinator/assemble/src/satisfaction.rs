@@ -1,7 +1,21 @@
 use assemble_integrity::*;
 use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::assembly::{get_assemblies_for_satisfaction, invalidate_assembly};
+use crate::call_to_action::get_latest_call_to_action;
+use crate::error::AssembleError;
 #[hdk_extern]
-pub fn create_satisfaction(satisfaction: Satisfaction) -> ExternResult<Record> {
+pub fn create_satisfaction(mut satisfaction: Satisfaction) -> ExternResult<Record> {
+    // Validation reads the need from the version of the call to action that the satisfaction references
+    if satisfaction.call_to_action_version_hash.is_none() {
+        let call_to_action_record =
+            get_latest_call_to_action(satisfaction.call_to_action_hash.clone())?.ok_or(
+                AssembleError::CallToActionNotFound(satisfaction.call_to_action_hash.clone()),
+            )?;
+        satisfaction.call_to_action_version_hash =
+            Some(call_to_action_record.action_address().clone());
+    }
     let satisfaction_hash = create_entry(&EntryTypes::Satisfaction(satisfaction.clone()))?;
     create_link(
         satisfaction.call_to_action_hash.clone(),
@@ -17,6 +31,15 @@ pub fn create_satisfaction(satisfaction: Satisfaction) -> ExternResult<Record> {
             (),
         )?;
     }
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    for agent in get_satisfaction_agents(&my_pub_key, &satisfaction)? {
+        create_link(
+            agent,
+            satisfaction_hash.clone(),
+            LinkTypes::AgentToSatisfactions,
+            (),
+        )?;
+    }
     let record = get(satisfaction_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
         WasmErrorInner::Guest(String::from(
             "Could not find the newly created Satisfaction"
@@ -66,6 +89,21 @@ pub fn delete_satisfaction(satisfaction_hash: ActionHash) -> ExternResult<()> {
         wasm_error!(WasmErrorInner::Guest("Could not find satisfaction".into())),
     )?)?;
 
+    // Validation only lets the author of the satisfaction delete the links from its agents
+    let author = satisfaction_record.action().author().clone();
+    if author.eq(&agent_info()?.agent_latest_pubkey) {
+        for agent in get_satisfaction_agents(&author, &satisfaction)? {
+            let links = get_links(agent, LinkTypes::AgentToSatisfactions, None)?;
+            for link in links {
+                if let Some(target) = link.target.into_action_hash() {
+                    if target.eq(&satisfaction_hash) {
+                        delete_link(link.create_link_hash)?;
+                    }
+                }
+            }
+        }
+    }
+
     let links = get_links(
         satisfaction.call_to_action_hash,
         LinkTypes::CallToActionToSatisfactions,
@@ -91,7 +129,14 @@ pub fn delete_satisfaction(satisfaction_hash: ActionHash) -> ExternResult<()> {
         }
     }
 
-    // delete_entry(satisfaction_hash)?;
+    // The assemblies built on this satisfaction don't hold anymore
+    for link in get_assemblies_for_satisfaction(satisfaction_hash.clone())? {
+        if let Some(assembly_hash) = link.target.into_action_hash() {
+            invalidate_assembly(assembly_hash)?;
+        }
+    }
+
+    delete_entry(satisfaction_hash)?;
 
     Ok(())
 }
@@ -107,11 +152,70 @@ pub fn get_satisfactions_for_call_to_action(
     )
 }
 
+/// Returns only one satisfaction per need: when several agents satisfy the same need
+/// concurrently, the satisfaction with the lowest action hash is the canonical one
+#[hdk_extern]
+pub fn get_canonical_satisfactions_for_call_to_action(
+    call_to_action_hash: ActionHash,
+) -> ExternResult<Vec<Link>> {
+    let links = get_links(
+        call_to_action_hash,
+        LinkTypes::CallToActionToSatisfactions,
+        None,
+    )?;
+
+    let mut canonical_links: BTreeMap<u32, Link> = BTreeMap::new();
+    for link in links {
+        let Some(satisfaction_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let satisfaction = Satisfaction::try_from(get_latest_satisfaction(satisfaction_hash)?)?;
+        match canonical_links.get(&satisfaction.need_index) {
+            Some(canonical_link) if canonical_link.target <= link.target => {}
+            _ => {
+                canonical_links.insert(satisfaction.need_index, link);
+            }
+        }
+    }
+
+    Ok(canonical_links.into_values().collect())
+}
+
+/// Returns all the satisfactions for the given need, including the ones that are not canonical
+pub fn get_all_satisfactions_for_need(
+    call_to_action_hash: ActionHash,
+    need_index: u32,
+) -> ExternResult<Vec<(ActionHash, Satisfaction)>> {
+    let links = get_links(
+        call_to_action_hash,
+        LinkTypes::CallToActionToSatisfactions,
+        None,
+    )?;
+
+    let mut satisfactions: Vec<(ActionHash, Satisfaction)> = vec![];
+    for satisfaction_hash in links
+        .into_iter()
+        .filter_map(|l| l.target.into_action_hash())
+    {
+        let satisfaction =
+            Satisfaction::try_from(get_latest_satisfaction(satisfaction_hash.clone())?)?;
+        if satisfaction.need_index == need_index {
+            satisfactions.push((satisfaction_hash, satisfaction));
+        }
+    }
+    Ok(satisfactions)
+}
+
 #[hdk_extern]
 pub fn get_satisfactions_for_commitment(commitment_hash: ActionHash) -> ExternResult<Vec<Link>> {
     get_links(commitment_hash, LinkTypes::CommitmentToSatisfactions, None)
 }
 
+#[hdk_extern]
+pub fn get_satisfactions_for_agent(agent: AgentPubKey) -> ExternResult<Vec<Link>> {
+    get_links(agent, LinkTypes::AgentToSatisfactions, None)
+}
+
 #[hdk_extern]
 pub fn get_satisfaction_deletes(
     satisfaction_hash: ActionHash,
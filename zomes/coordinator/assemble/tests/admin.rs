@@ -0,0 +1,286 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::admin::{
+    AdminHistoryItem, ChangeAdminInput, TransferOwnershipInput,
+};
+use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+
+mod common;
+use common::{create_call_to_action, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn admins_can_be_added_removed_and_the_ownership_transferred() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, sample).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    // Alice makes Bob an admin
+    let record: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "add_admin",
+            ChangeAdminInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                admin: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    let call_to_action = CallToAction::try_from(record).unwrap();
+    assert_eq!(call_to_action.admins, vec![bobbo.agent_pubkey().clone()]);
+
+    // Bob is the only admin, so they can't be removed
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "remove_admin",
+            ChangeAdminInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                admin: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob is an admin but not the owner, so they can't take the ownership
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob_zome,
+            "transfer_ownership",
+            TransferOwnershipInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                new_owner: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Alice transfers the ownership to Bob, and stays as an admin
+    let record: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "transfer_ownership",
+            TransferOwnershipInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                new_owner: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    let call_to_action = CallToAction::try_from(record).unwrap();
+    assert_eq!(call_to_action.owner, Some(bobbo.agent_pubkey().clone()));
+    assert_eq!(call_to_action.admins, vec![alice.agent_pubkey().clone()]);
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob, as the new owner, can update the call to action
+    let record: Record = conductors[1]
+        .call(
+            &bob_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: record.signed_action.action_address().clone(),
+                updated_call_to_action: CallToAction {
+                    tags: vec![String::from("transferred")],
+                    ..call_to_action
+                },
+            },
+        )
+        .await;
+    let call_to_action = CallToAction::try_from(record).unwrap();
+    assert_eq!(call_to_action.owner, Some(bobbo.agent_pubkey().clone()));
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let history: Vec<AdminHistoryItem> = conductors[1]
+        .call(&bob_zome, "get_admin_history", call_to_action_hash)
+        .await;
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history[0].changes,
+        vec![AdminChange::AdminAdded {
+            admin: bobbo.agent_pubkey().clone()
+        }]
+    );
+    assert_eq!(
+        history[1].changes,
+        vec![
+            AdminChange::OwnershipTransferred {
+                previous_owner: alice.agent_pubkey().clone(),
+                new_owner: bobbo.agent_pubkey().clone(),
+            },
+            AdminChange::AdminAdded {
+                admin: alice.agent_pubkey().clone()
+            },
+            AdminChange::AdminRemoved {
+                admin: bobbo.agent_pubkey().clone()
+            },
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_links_reference_the_version_that_added_the_admin() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, sample).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    // Alice makes Bob an admin
+    let record: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "add_admin",
+            ChangeAdminInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                admin: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_for_admin",
+            bobbo.agent_pubkey().clone(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target.clone().into_action_hash(),
+        Some(call_to_action_hash)
+    );
+    let tag = AdminLinkTag::try_from(links[0].tag.clone()).unwrap();
+    assert_eq!(
+        &tag.call_to_action_version_hash,
+        record.signed_action.action_address()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn admins_cant_change_while_they_have_to_approve_or_countersign() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    // Alice creates a CallToAction with a need that requires their approval
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let call_to_action = CallToAction {
+        needs: vec![Need {
+            requires_admin_approval: true,
+            ..sample.needs[0].clone()
+        }],
+        ..sample
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action.clone()).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "add_admin",
+            ChangeAdminInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                admin: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "transfer_ownership",
+            TransferOwnershipInput {
+                call_to_action_hash: call_to_action_hash.clone(),
+                new_owner: bobbo.agent_pubkey().clone(),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // The need can't stop requiring approval either, which would let the admins change
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: call_to_action_hash.clone(),
+                updated_call_to_action: CallToAction {
+                    needs: vec![Need {
+                        requires_admin_approval: false,
+                        ..call_to_action.needs[0].clone()
+                    }],
+                    ..call_to_action.clone()
+                },
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: call_to_action_hash,
+                updated_call_to_action: CallToAction {
+                    countersigned_assembly: true,
+                    ..call_to_action
+                },
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
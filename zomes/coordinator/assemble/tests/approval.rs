@@ -0,0 +1,330 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::approval::RejectCommitmentInput;
+use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
+
+mod common;
+use common::{create_call_to_action, create_commitment, sample_call_to_action_2};
+
+async fn create_call_to_action_requiring_approval(
+    conductor: &SweetConductor,
+    zome: &SweetZome,
+) -> ActionHash {
+    let sample = sample_call_to_action_2(conductor, zome).await;
+    let call_to_action = CallToAction {
+        needs: vec![Need {
+            requires_admin_approval: true,
+            ..sample.needs[0].clone()
+        }],
+        ..sample
+    };
+    let record = create_call_to_action(conductor, zome, call_to_action).await;
+    record.signed_action.action_address().clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn approved_commitments_satisfy_their_need() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let call_to_action_hash =
+        create_call_to_action_requiring_approval(&conductors[0], &alice_zome).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob commits enough to satisfy the need, but Alice hasn't approved it yet
+    let record = create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: call_to_action_hash.clone(),
+            amount: 4,
+            comment: None,
+            need_index: 0,
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+    let commitment_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let pending: Vec<ActionHash> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_pending_approvals_for_admin",
+            alice.agent_pubkey().clone(),
+        )
+        .await;
+    assert_eq!(pending, vec![commitment_hash.clone()]);
+
+    // Bob is not an admin, so they can't approve their own commitment
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(&bob_zome, "approve_commitment", commitment_hash.clone())
+        .await;
+    assert!(result.is_err());
+
+    let _approval: Record = conductors[0]
+        .call(&alice_zome, "approve_commitment", commitment_hash.clone())
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let pending: Vec<ActionHash> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_pending_approvals_for_admin",
+            alice.agent_pubkey().clone(),
+        )
+        .await;
+    assert!(pending.is_empty());
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejected_commitments_are_not_pending_nor_approvable() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let call_to_action_hash =
+        create_call_to_action_requiring_approval(&conductors[0], &alice_zome).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let record = create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: call_to_action_hash.clone(),
+            amount: 4,
+            comment: None,
+            need_index: 0,
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+    let commitment_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // A rejection needs a reason
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "reject_commitment",
+            RejectCommitmentInput {
+                commitment_hash: commitment_hash.clone(),
+                reason: String::from(""),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let rejection: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "reject_commitment",
+            RejectCommitmentInput {
+                commitment_hash: commitment_hash.clone(),
+                reason: String::from("We already have enough budget from another source"),
+            },
+        )
+        .await;
+    let rejection = CommitmentRejection::try_from(rejection).unwrap();
+    assert_eq!(rejection.commitment_hash, commitment_hash);
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let pending: Vec<ActionHash> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_pending_approvals_for_admin",
+            alice.agent_pubkey().clone(),
+        )
+        .await;
+    assert!(pending.is_empty());
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "approve_commitment", commitment_hash)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitments_need_the_approval_threshold_of_admins() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice and Bob both have to approve the commitments for the need
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let call_to_action = CallToAction {
+        admins: vec![bobbo.agent_pubkey().clone()],
+        needs: vec![Need {
+            requires_admin_approval: true,
+            approval_threshold: 2,
+            ..sample.needs[0].clone()
+        }],
+        ..sample
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action.clone()).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    // There are not enough admins to reach a threshold of 3
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_call_to_action",
+            CallToAction {
+                needs: vec![Need {
+                    approval_threshold: 3,
+                    ..call_to_action.needs[0].clone()
+                }],
+                ..call_to_action.clone()
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Alice listing themselves as an admin doesn't let them approve twice
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_call_to_action",
+            CallToAction {
+                admins: vec![alice.agent_pubkey().clone()],
+                ..call_to_action.clone()
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob's updates as an admin still count Alice among the approvers
+    let _update_record: Record = conductors[1]
+        .call(
+            &bob_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: call_to_action_hash.clone(),
+                updated_call_to_action: CallToAction {
+                    tags: vec![String::from("approvals")],
+                    ..call_to_action
+                },
+            },
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let record = create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: call_to_action_hash.clone(),
+            amount: 4,
+            comment: None,
+            need_index: 0,
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+    let commitment_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let _approval: Record = conductors[0]
+        .call(&alice_zome, "approve_commitment", commitment_hash.clone())
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // One approval is not enough
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(!progress.needs[0].satisfied);
+
+    let pending: Vec<ActionHash> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_pending_approvals_for_admin",
+            bobbo.agent_pubkey().clone(),
+        )
+        .await;
+    assert_eq!(pending, vec![commitment_hash.clone()]);
+
+    let _approval: Record = conductors[1]
+        .call(&bob_zome, "approve_commitment", commitment_hash)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+}
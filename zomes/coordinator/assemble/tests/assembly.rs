@@ -7,11 +7,14 @@ use holochain::test_utils::consistency_10s;
 use holochain::{conductor::config::ConductorConfig, sweettest::*};
 
 use assemble_integrity::*;
+use hc_zome_assemble_coordinator::call_to_action::CallToActionStatus;
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
 
 mod common;
 use common::{create_assembly, sample_assembly_1, sample_assembly_2};
 
 use common::{create_call_to_action, sample_call_to_action_1, sample_call_to_action_2};
+use common::{create_commitment, sample_full_commitment_2};
 use common::{create_satisfaction, sample_satisfaction_1, sample_satisfaction_2};
 
 #[tokio::test(flavor = "multi_thread")]
@@ -74,3 +77,471 @@ async fn create_and_read_assembly() {
 
     assert_eq!(record, get_record.unwrap());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_commitments_result_in_one_canonical_satisfaction_and_assembly() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction with one need that can be satisfied by any of the commitments
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_2(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let alice_commitment = sample_full_commitment_2(
+        &conductors[0],
+        &alice_zome,
+        Some(call_to_action_hash.clone()),
+    )
+    .await;
+    let bob_commitment =
+        sample_full_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone()))
+            .await;
+
+    // Alice and Bob commit at the same time, so each of them satisfies the need and assembles
+    tokio::join!(
+        create_commitment(&conductors[0], &alice_zome, alice_commitment),
+        create_commitment(&conductors[1], &bob_zome, bob_commitment),
+    );
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // The race happened: both of them satisfied the need and assembled
+    let all_satisfactions: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_satisfactions_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert_eq!(all_satisfactions.len(), 2);
+    let all_assemblies: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_assemblies_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert_eq!(all_assemblies.len(), 2);
+
+    // But they agree on the canonical ones
+    let alice_satisfactions: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_canonical_satisfactions_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let bob_satisfactions: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_canonical_satisfactions_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert_eq!(alice_satisfactions.len(), 1);
+    assert_eq!(alice_satisfactions[0].target, bob_satisfactions[0].target);
+
+    let alice_assembly: Option<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_canonical_assembly_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let bob_assembly: Option<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_canonical_assembly_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let alice_assembly = alice_assembly.unwrap();
+    assert_eq!(alice_assembly.target, bob_assembly.unwrap().target);
+
+    // The canonical assembly is built with the canonical satisfaction
+    let assembly_record: Option<Record> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_assembly",
+            alice_assembly.target.into_action_hash().unwrap(),
+        )
+        .await;
+    let assembly = Assembly::try_from(assembly_record.unwrap()).unwrap();
+    assert_eq!(
+        assembly.satisfactions_hashes,
+        vec![alice_satisfactions[0]
+            .target
+            .clone()
+            .into_action_hash()
+            .unwrap()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_assemblies_are_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (_bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    // Alice creates a CallToAction with two required needs
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            needs: vec![
+                sample.needs[0].clone(),
+                Need {
+                    description: "Lorem ipsum 3".to_string(),
+                    ..sample.needs[0].clone()
+                },
+            ],
+            ..sample
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    let mut satisfactions_hashes: Vec<ActionHash> = vec![];
+    for need_index in 0..2 {
+        let commitment_hash = create_commitment(
+            &conductors[0],
+            &alice_zome,
+            Commitment {
+                need_index,
+                ..sample_full_commitment_2(
+                    &conductors[0],
+                    &alice_zome,
+                    Some(call_to_action_hash.clone()),
+                )
+                .await
+            },
+        )
+        .await
+        .signed_action
+        .hashed
+        .hash;
+        let satisfaction = Satisfaction {
+            call_to_action_hash: call_to_action_hash.clone(),
+            need_index,
+            commitments_hashes: vec![commitment_hash],
+            approvals_hashes: vec![],
+            call_to_action_version_hash: None,
+        };
+        satisfactions_hashes.push(
+            create_satisfaction(&conductors[0], &alice_zome, satisfaction)
+                .await
+                .signed_action
+                .hashed
+                .hash,
+        );
+    }
+
+    // A second satisfaction for the first need
+    let duplicate_satisfaction_hash = create_satisfaction(
+        &conductors[0],
+        &alice_zome,
+        sample_satisfaction_2(
+            &conductors[0],
+            &alice_zome,
+            Some(call_to_action_hash.clone()),
+        )
+        .await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    // A satisfaction for another CallToAction
+    let foreign_satisfaction_hash = create_satisfaction(
+        &conductors[0],
+        &alice_zome,
+        sample_satisfaction_2(&conductors[0], &alice_zome, None).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    // Leaving out a required need is not allowed
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_assembly",
+            Assembly {
+                call_to_action_hash: call_to_action_hash.clone(),
+                satisfactions_hashes: vec![satisfactions_hashes[0].clone()],
+                children_assemblies_hashes: vec![],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Satisfactions for other calls to action are not allowed
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_assembly",
+            Assembly {
+                call_to_action_hash: call_to_action_hash.clone(),
+                satisfactions_hashes: vec![
+                    satisfactions_hashes[0].clone(),
+                    satisfactions_hashes[1].clone(),
+                    foreign_satisfaction_hash,
+                ],
+                children_assemblies_hashes: vec![],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Two satisfactions for the same need are not allowed
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_assembly",
+            Assembly {
+                call_to_action_hash: call_to_action_hash.clone(),
+                satisfactions_hashes: vec![
+                    satisfactions_hashes[0].clone(),
+                    satisfactions_hashes[1].clone(),
+                    duplicate_satisfaction_hash,
+                ],
+                children_assemblies_hashes: vec![],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // One satisfaction for each need is a valid assembly
+    create_assembly(
+        &conductors[0],
+        &alice_zome,
+        Assembly {
+            call_to_action_hash,
+            satisfactions_hashes,
+            children_assemblies_hashes: vec![],
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deleting_a_satisfaction_invalidates_its_assembly() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_2(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    // Alice commits enough to satisfy the only need, so the call to action gets assembled
+    create_commitment(
+        &conductors[0],
+        &alice_zome,
+        sample_full_commitment_2(
+            &conductors[0],
+            &alice_zome,
+            Some(call_to_action_hash.clone()),
+        )
+        .await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[1]
+        .call(
+            &bob_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let satisfaction_hash = progress.needs[0].satisfaction_hash.clone().unwrap();
+    let assembly_hash = progress.assembly_hash.unwrap();
+
+    // Bob is not involved in the satisfaction nor an admin, so they can't delete it
+    let result: Result<(), _> = conductors[1]
+        .call_fallible(&bob_zome, "delete_satisfaction", satisfaction_hash.clone())
+        .await;
+    assert!(result.is_err());
+
+    let _: () = conductors[0]
+        .call(&alice_zome, "delete_satisfaction", satisfaction_hash)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let deletes: Vec<SignedActionHashed> = conductors[1]
+        .call(&bob_zome, "get_assembly_deletes", assembly_hash)
+        .await;
+    assert_eq!(deletes.len(), 1);
+
+    let assembly: Option<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_canonical_assembly_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(assembly.is_none());
+
+    let status: CallToActionStatus = conductors[1]
+        .call(&bob_zome, "get_call_to_action_status", call_to_action_hash)
+        .await;
+    assert_eq!(status, CallToActionStatus::Open);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn scheduled_repair_recreates_missing_satisfactions_and_assemblies() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_2(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    create_commitment(
+        &conductors[0],
+        &alice_zome,
+        sample_full_commitment_2(
+            &conductors[0],
+            &alice_zome,
+            Some(call_to_action_hash.clone()),
+        )
+        .await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Alice deletes the satisfaction, although the commitment still satisfies the need
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let _: () = conductors[0]
+        .call(
+            &alice_zome,
+            "delete_satisfaction",
+            progress.needs[0].satisfaction_hash.clone().unwrap(),
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(!progress.needs[0].satisfied);
+    assert!(!progress.assembled);
+
+    // The scheduled job satisfies the need and assembles the call to action again, and runs again in an hour
+    let schedule: Option<Schedule> = conductors[0]
+        .call(
+            &alice_zome,
+            "create_satisfactions_or_assemblies_for_my_incosistent_calls_to_action",
+            None::<Schedule>,
+        )
+        .await;
+    assert_eq!(
+        schedule,
+        Some(Schedule::Persisted(String::from("0 0 * * * *")))
+    );
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+    assert!(progress.assembled);
+}
@@ -8,10 +8,11 @@ use holochain::{conductor::config::ConductorConfig, sweettest::*};
 
 use assemble_integrity::*;
 
-use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+use hc_zome_assemble_coordinator::call_to_action::{CallToActionStatus, UpdateCallToActionInput};
 
 mod common;
 use common::{create_call_to_action, sample_call_to_action_1, sample_call_to_action_2};
+use common::{create_commitment, sample_commitment_1, sample_commitment_2};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn create_call_to_action_test() {
@@ -197,3 +198,320 @@ async fn create_and_delete_call_to_action() {
 
     assert!(get_record.is_none());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_author_and_admins_can_update_or_delete_call_to_action() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+
+    // Alice creates a CallToAction
+    let record: Record = create_call_to_action(&conductors[0], &alice_zome, sample_1.clone()).await;
+    let original_action_hash = record.signed_action.hashed.hash.clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob is not an admin, so they can't update nor delete it
+    let sample_2 = sample_call_to_action_2(&conductors[1], &bob_zome).await;
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: sample_2.clone(),
+    };
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(&bob_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_err());
+
+    let result: Result<ActionHash, _> = conductors[1]
+        .call_fallible(
+            &bob_zome,
+            "delete_call_to_action",
+            original_action_hash.clone(),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Alice makes Bob an admin
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            admins: vec![bobbo.agent_pubkey().clone()],
+            ..sample_1.clone()
+        },
+    };
+    let update_record: Record = conductors[0]
+        .call(&alice_zome, "update_call_to_action", input)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Now Bob can update it
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: update_record.signed_action.hashed.hash.clone(),
+        updated_call_to_action: CallToAction {
+            admins: vec![bobbo.agent_pubkey().clone()],
+            ..sample_2.clone()
+        },
+    };
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(&bob_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn needs_with_commitments_cannot_be_removed() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    let sample_2 = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+
+    // Alice creates a CallToAction
+    let record: Record = create_call_to_action(&conductors[0], &alice_zome, sample_2.clone()).await;
+    let original_action_hash = record.signed_action.hashed.hash.clone();
+
+    // Alice commits to its only need
+    create_commitment(
+        &conductors[0],
+        &alice_zome,
+        sample_commitment_2(
+            &conductors[0],
+            &alice_zome,
+            Some(original_action_hash.clone()),
+        )
+        .await,
+    )
+    .await;
+
+    // Removing the need is not allowed anymore
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            needs: vec![],
+            ..sample_2.clone()
+        },
+    };
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_err());
+
+    // Replacing the need with a different one is not allowed either
+    let mut needs = sample_2.needs.clone();
+    needs[0].description = "Lorem ipsum 3".to_string();
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            needs,
+            ..sample_2.clone()
+        },
+    };
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_err());
+
+    // Changing the need while keeping its description is not allowed either
+    let mut needs = sample_2.needs.clone();
+    needs[0].max_possible = Some(10);
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            needs,
+            ..sample_2.clone()
+        },
+    };
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_err());
+
+    // Adding needs at the end is still allowed
+    let mut needs = sample_2.needs.clone();
+    needs.push(Need {
+        requires_admin_approval: false,
+        min_necessary: 1,
+        max_possible: None,
+        description: "Lorem ipsum 3".to_string(),
+        approval_threshold: 1,
+    });
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: original_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            needs,
+            ..sample_2.clone()
+        },
+    };
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "update_call_to_action", input)
+        .await;
+    assert!(result.is_ok());
+
+    // The new need can be committed to, since the commitment references the update that added it
+    let commitment_record = create_commitment(
+        &conductors[0],
+        &alice_zome,
+        Commitment {
+            need_index: 1,
+            ..sample_commitment_2(
+                &conductors[0],
+                &alice_zome,
+                Some(original_action_hash.clone()),
+            )
+            .await
+        },
+    )
+    .await;
+    let commitment = Commitment::try_from(commitment_record).unwrap();
+    assert_eq!(
+        commitment.call_to_action_version_hash,
+        Some(result.unwrap().signed_action.hashed.hash)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_to_action_status_follows_its_lifecycle() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_1(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    let status: CallToActionStatus = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_status",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert_eq!(status, CallToActionStatus::Open);
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob commits enough to fulfill it, so it gets assembled
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        sample_commitment_1(&conductors[1], &bob_zome, Some(call_to_action_hash.clone())).await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let status: CallToActionStatus = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_status",
+            call_to_action_hash,
+        )
+        .await;
+    assert_eq!(status, CallToActionStatus::Fulfilled);
+
+    // A CallToAction that expired an hour ago
+    let an_hour_ago = Timestamp::from_micros(Timestamp::now().as_micros() - 60 * 60 * 1_000_000);
+    let expired_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(an_hour_ago),
+            ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    let status: CallToActionStatus = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_status",
+            expired_hash.clone(),
+        )
+        .await;
+    assert_eq!(status, CallToActionStatus::Expired);
+
+    // The expiration can't be brought forward
+    let two_hours_ago =
+        Timestamp::from_micros(Timestamp::now().as_micros() - 2 * 60 * 60 * 1_000_000);
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: expired_hash,
+                updated_call_to_action: CallToAction {
+                    expiration_time: Some(two_hours_ago),
+                    ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+                },
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // A CallToAction that Alice deletes
+    let deleted_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_2(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+    let _: ActionHash = conductors[0]
+        .call(&alice_zome, "delete_call_to_action", deleted_hash.clone())
+        .await;
+
+    let status: CallToActionStatus = conductors[0]
+        .call(&alice_zome, "get_call_to_action_status", deleted_hash)
+        .await;
+    assert_eq!(status, CallToActionStatus::Cancelled);
+}
@@ -0,0 +1,234 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::cancellations::{
+    CancelCommitmentInput, UndoCommitmentCancellationInput,
+};
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
+
+mod common;
+use common::sample_full_commitment_2;
+use common::{
+    create_call_to_action, create_commitment, sample_call_to_action_2, sample_commitment_2,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cancelling_a_commitment_invalidates_its_satisfaction_and_assembly() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_2(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob commits enough to satisfy the only need, so the call to action gets assembled
+    let commitment_hash = create_commitment(
+        &conductors[1],
+        &bob_zome,
+        sample_full_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone()))
+            .await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+    assert!(progress.assembled);
+    let assembly_hash = progress.assembly_hash.unwrap();
+
+    // Bob cancels their commitment
+    let cancellation: Record = conductors[1]
+        .call(
+            &bob_zome,
+            "cancel_commitment",
+            CancelCommitmentInput {
+                commitment_hash: commitment_hash.clone(),
+                reason: String::from("Oh sorry can't bring this"),
+            },
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(!progress.needs[0].satisfied);
+    assert!(!progress.assembled);
+
+    let deletes: Vec<SignedActionHashed> = conductors[0]
+        .call(&alice_zome, "get_assembly_deletes", assembly_hash)
+        .await;
+    assert_eq!(deletes.len(), 1);
+
+    // Bob undoes the cancellation, so the call to action gets satisfied and assembled again
+    let _: () = conductors[1]
+        .call(
+            &bob_zome,
+            "undo_commitment_cancellation",
+            UndoCommitmentCancellationInput {
+                commitment_hash,
+                cancellation_hash: cancellation.signed_action.hashed.hash,
+            },
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+    assert!(progress.assembled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitments_can_be_cancelled_after_the_call_to_action_expires() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction that expires in 20 seconds
+    let in_twenty_seconds = Timestamp::from_micros(Timestamp::now().as_micros() + 20 * 1_000_000);
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_twenty_seconds),
+            ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Alice commits part of the need, and Bob commits enough to satisfy it by themselves
+    let commitment_hash = create_commitment(
+        &conductors[0],
+        &alice_zome,
+        sample_commitment_2(
+            &conductors[0],
+            &alice_zome,
+            Some(call_to_action_hash.clone()),
+        )
+        .await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        sample_full_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone()))
+            .await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+
+    // Wait for the call to action to expire
+    let until_expiration = in_twenty_seconds.as_micros() - Timestamp::now().as_micros();
+    if until_expiration > 0 {
+        tokio::time::sleep(std::time::Duration::from_micros(
+            until_expiration as u64 + 1_000_000,
+        ))
+        .await;
+    }
+
+    // Alice cancels their commitment: the satisfaction that relied on it is deleted,
+    // and Bob's commitment doesn't satisfy the need again since the call to action has expired
+    let _: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "cancel_commitment",
+            CancelCommitmentInput {
+                commitment_hash,
+                reason: String::from("Too late for me"),
+            },
+        )
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(!progress.needs[0].satisfied);
+}
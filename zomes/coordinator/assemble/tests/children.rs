@@ -0,0 +1,312 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::call_to_action::{CallToActionStatus, UpdateCallToActionInput};
+use hc_zome_assemble_coordinator::children::CallToActionTree;
+
+mod common;
+use common::{create_call_to_action, create_commitment, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parent_assembles_only_after_its_children() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a parent CallToAction that waits for all its children, and one child
+    let parent = CallToAction {
+        children_policy: ChildrenPolicy::AllChildren,
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, parent).await;
+    let parent_hash = record.signed_action.action_address().clone();
+
+    let child = CallToAction {
+        parent_call_to_action_hash: Some(parent_hash.clone()),
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, child).await;
+    let child_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob satisfies the need of the parent, but the child hasn't assembled yet
+    let commitment = Commitment {
+        call_to_action_hash: parent_hash.clone(),
+        amount: 4,
+        comment: None,
+        need_index: 0,
+        call_to_action_version_hash: None,
+    };
+    create_commitment(&conductors[1], &bob_zome, commitment.clone()).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let tree: CallToActionTree = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_subtree",
+            parent_hash.clone(),
+        )
+        .await;
+    assert_eq!(tree.call_to_action_hash, parent_hash);
+    assert_eq!(tree.status, CallToActionStatus::Open);
+    assert_eq!(tree.children.len(), 1);
+    assert_eq!(tree.children[0].call_to_action_hash, child_hash);
+    assert_eq!(tree.children[0].status, CallToActionStatus::Open);
+
+    // Bob satisfies the need of the child, which assembles the child and then the parent
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: child_hash.clone(),
+            ..commitment
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let tree: CallToActionTree = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_subtree",
+            parent_hash.clone(),
+        )
+        .await;
+    assert_eq!(tree.status, CallToActionStatus::Fulfilled);
+    assert_eq!(tree.children[0].status, CallToActionStatus::Fulfilled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_owner_and_the_admins_can_create_children() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a parent CallToAction that waits for all its children
+    let parent = CallToAction {
+        children_policy: ChildrenPolicy::AllChildren,
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, parent).await;
+    let parent_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob is not an admin of the parent, so they can't block it with a child of their own
+    let child = CallToAction {
+        parent_call_to_action_hash: Some(parent_hash.clone()),
+        ..sample_call_to_action_2(&conductors[1], &bob_zome).await
+    };
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(&bob_zome, "create_call_to_action", child.clone())
+        .await;
+    assert!(result.is_err());
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let children: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_actions_for_call_to_action",
+            parent_hash.clone(),
+        )
+        .await;
+    assert!(children.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parent_assembles_after_the_children_set_in_an_update() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a parent CallToAction and two children
+    let parent = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, parent.clone()).await;
+    let parent_hash = record.signed_action.action_address().clone();
+
+    let child = CallToAction {
+        parent_call_to_action_hash: Some(parent_hash.clone()),
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, child.clone()).await;
+    let required_child_hash = record.signed_action.action_address().clone();
+    create_call_to_action(&conductors[0], &alice_zome, child).await;
+
+    // The children only exist after their parent, so Alice requires one of them in an update
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: parent_hash.clone(),
+        updated_call_to_action: CallToAction {
+            children_policy: ChildrenPolicy::SomeChildren {
+                children_hashes: vec![required_child_hash.clone()],
+            },
+            ..parent
+        },
+    };
+    let _update_record: Record = conductors[0]
+        .call(&alice_zome, "update_call_to_action", input)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob satisfies the need of the parent, but the required child hasn't assembled yet
+    let commitment = Commitment {
+        call_to_action_hash: parent_hash.clone(),
+        amount: 4,
+        comment: None,
+        need_index: 0,
+        call_to_action_version_hash: None,
+    };
+    create_commitment(&conductors[1], &bob_zome, commitment.clone()).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let tree: CallToActionTree = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_subtree",
+            parent_hash.clone(),
+        )
+        .await;
+    assert_eq!(tree.status, CallToActionStatus::Open);
+
+    // Bob satisfies the need of the required child, which assembles the child and then the parent
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: required_child_hash.clone(),
+            ..commitment
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let tree: CallToActionTree = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_subtree",
+            parent_hash.clone(),
+        )
+        .await;
+    assert_eq!(tree.status, CallToActionStatus::Fulfilled);
+    for child in tree.children {
+        if child.call_to_action_hash.eq(&required_child_hash) {
+            assert_eq!(child.status, CallToActionStatus::Fulfilled);
+        } else {
+            assert_eq!(child.status, CallToActionStatus::Open);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn children_admins_can_update_them_but_not_their_parent() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a parent and a child administered by Bob, who is not an admin of the parent
+    let parent = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, parent.clone()).await;
+    let parent_hash = record.signed_action.action_address().clone();
+    let record = create_call_to_action(&conductors[0], &alice_zome, parent).await;
+    let other_parent_hash = record.signed_action.action_address().clone();
+
+    let child = CallToAction {
+        admins: vec![bobbo.agent_pubkey().clone()],
+        parent_call_to_action_hash: Some(parent_hash),
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, child.clone()).await;
+    let child_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let record: Record = conductors[1]
+        .call(
+            &bob_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: child_hash,
+                updated_call_to_action: CallToAction {
+                    tags: vec![String::from("child")],
+                    ..child.clone()
+                },
+            },
+        )
+        .await;
+
+    // The child can't be moved to another parent
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: record.signed_action.action_address().clone(),
+                updated_call_to_action: CallToAction {
+                    parent_call_to_action_hash: Some(other_parent_hash),
+                    ..child
+                },
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -73,3 +73,195 @@ async fn create_and_read_commitment() {
 
     assert_eq!(record, get_record.unwrap());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitment_over_max_possible_is_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (_bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    let sample = sample_commitment_1(&conductors[0], &alice_zome, None).await;
+
+    // Alice commits the max_possible amount for the need
+    create_commitment(&conductors[0], &alice_zome, sample.clone()).await;
+
+    // Committing again would exceed the max_possible
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "create_commitment", sample.clone())
+        .await;
+    assert!(result.is_err());
+
+    // A need_index out of range for the call to action is rejected
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_commitment",
+            Commitment {
+                need_index: 1,
+                ..sample
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitment_link_tag_carries_need_index_amount_and_committer() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample = sample_commitment_1(&conductors[0], &alice_zome, None).await;
+
+    // Alice creates a Commitment
+    create_commitment(&conductors[0], &alice_zome, sample.clone()).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_commitments_for_call_to_action",
+            sample.call_to_action_hash.clone(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+
+    let tag = CommitmentLinkTag::try_from(links[0].tag.clone()).unwrap();
+    assert_eq!(
+        tag,
+        CommitmentLinkTag::new(&sample, alice.agent_pubkey().clone())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_and_commitments_are_indexed_by_agent() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction and Bob commits to it
+    let call_to_action = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let sample =
+        sample_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone())).await;
+    let commitment_record = create_commitment(&conductors[1], &bob_zome, sample).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_calls_to_action_for_agent",
+            alice.agent_pubkey().clone(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].target, AnyLinkableHash::from(call_to_action_hash));
+
+    let links: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_commitments_for_agent",
+            bobbo.agent_pubkey().clone(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target,
+        AnyLinkableHash::from(commitment_record.signed_action.action_address().clone())
+    );
+
+    let links: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_commitments_for_agent",
+            alice.agent_pubkey().clone(),
+        )
+        .await;
+    assert!(links.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitments_to_expired_calls_to_action_are_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction that expired an hour ago
+    let an_hour_ago = Timestamp::from_micros(Timestamp::now().as_micros() - 60 * 60 * 1_000_000);
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(an_hour_ago),
+            ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let result: Result<Record, _> = conductors[1]
+        .call_fallible(
+            &bob_zome,
+            "create_commitment",
+            sample_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash)).await,
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -1,4 +1,5 @@
 use hdk::prelude::*;
+use holochain::prelude::DnaFile;
 use holochain::sweettest::*;
 
 use assemble_integrity::*;
@@ -13,7 +14,14 @@ pub async fn sample_call_to_action_1(conductor: &SweetConductor, zome: &SweetZom
             min_necessary: 4,
             max_possible: Some(4),
             description: "Lorem ipsum 1".to_string(),
+            approval_threshold: 1,
         }],
+        tags: vec!["gardening".to_string()],
+        location: None,
+        children_policy: ChildrenPolicy::Independent,
+        fulfillment_policy: FulfillmentPolicy::AllRequiredNeeds,
+        countersigned_assembly: false,
+        owner: None,
     }
 }
 
@@ -27,7 +35,14 @@ pub async fn sample_call_to_action_2(conductor: &SweetConductor, zome: &SweetZom
             min_necessary: 4,
             max_possible: None,
             description: "Lorem ipsum 2".to_string(),
+            approval_threshold: 1,
         }],
+        tags: vec![],
+        location: None,
+        children_policy: ChildrenPolicy::Independent,
+        fulfillment_policy: FulfillmentPolicy::AllRequiredNeeds,
+        countersigned_assembly: false,
+        owner: None,
     }
 }
 
@@ -66,6 +81,7 @@ pub async fn sample_commitment_1(
         amount: 4,
         comment: Some("Lorem ipsum dolor sit amet, consectetur adipiscing elit.".to_string()),
         need_index: 0,
+        call_to_action_version_hash: None,
     }
 }
 
@@ -93,6 +109,19 @@ pub async fn sample_commitment_2(
         amount: 1,
         comment: Some("Lorem ipsum 2".to_string()),
         need_index: 0,
+        call_to_action_version_hash: None,
+    }
+}
+
+/// A commitment that reaches the min_necessary of the need of sample_call_to_action_2 by itself
+pub async fn sample_full_commitment_2(
+    conductor: &SweetConductor,
+    zome: &SweetZome,
+    call_to_action_hash: Option<ActionHash>,
+) -> Commitment {
+    Commitment {
+        amount: 4,
+        ..sample_commitment_2(conductor, zome, call_to_action_hash).await
     }
 }
 
@@ -138,6 +167,8 @@ pub async fn sample_satisfaction_1(
             .hashed
             .hash,
         ],
+        approvals_hashes: vec![],
+        call_to_action_version_hash: None,
     }
 }
 
@@ -167,13 +198,15 @@ pub async fn sample_satisfaction_2(
             create_commitment(
                 conductor,
                 zome,
-                sample_commitment_2(conductor, zome, Some(call_to_action_hash)).await,
+                sample_full_commitment_2(conductor, zome, Some(call_to_action_hash)).await,
             )
             .await
             .signed_action
             .hashed
             .hash,
         ],
+        approvals_hashes: vec![],
+        call_to_action_version_hash: None,
     }
 }
 
@@ -220,6 +253,8 @@ pub async fn sample_assembly_1(
             .hashed
             .hash,
         ],
+        children_assemblies_hashes: vec![],
+        call_to_action_version_hash: None,
     }
 }
 
@@ -255,6 +290,8 @@ pub async fn sample_assembly_2(
             .hashed
             .hash,
         ],
+        children_assemblies_hashes: vec![],
+        call_to_action_version_hash: None,
     }
 }
 
@@ -266,3 +303,30 @@ pub async fn create_assembly(
     let record: Record = conductor.call(zome, "create_assembly", assembly).await;
     record
 }
+
+/// Installs the DNA for the agent with the given membrane proof, which fails if genesis rejects the proof
+pub async fn install_with_membrane_proof(
+    conductor: &SweetConductor,
+    installed_app_id: &str,
+    agent: AgentPubKey,
+    dna: &DnaFile,
+    membrane_proof: Option<SerializedBytes>,
+) -> bool {
+    let installed = conductor
+        .raw_handle()
+        .install_app_legacy(
+            installed_app_id.to_string(),
+            agent,
+            &[(dna.clone(), membrane_proof.map(MembraneProof::new))],
+        )
+        .await;
+    if installed.is_err() {
+        return false;
+    }
+    conductor
+        .raw_handle()
+        .enable_app(installed_app_id.to_string())
+        .await
+        .unwrap();
+    true
+}
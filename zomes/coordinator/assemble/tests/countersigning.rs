@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
+
+mod common;
+use common::{create_call_to_action, create_commitment, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn countersigned_assembly_is_signed_by_the_author_and_all_admins() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction administered by Bob that has to be assembled by both of them
+    let call_to_action = CallToAction {
+        admins: vec![bobbo.agent_pubkey().clone()],
+        countersigned_assembly: true,
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob satisfies the need, but the assembly is not created automatically
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: call_to_action_hash.clone(),
+            amount: 4,
+            comment: None,
+            need_index: 0,
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+    assert!(!progress.assembled);
+
+    // An assembly signed only by Alice is not valid
+    let satisfactions: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_satisfactions_for_call_to_action",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_assembly",
+            Assembly {
+                call_to_action_hash: call_to_action_hash.clone(),
+                satisfactions_hashes: satisfactions
+                    .into_iter()
+                    .filter_map(|l| l.target.into_action_hash())
+                    .collect(),
+                children_assemblies_hashes: vec![],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let record: Record = conductors[0]
+        .call(
+            &alice_zome,
+            "create_countersigned_assembly",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    let Some(Entry::CounterSign(session_data, _)) = record.entry().as_option() else {
+        panic!("The assembly is not countersigned");
+    };
+    let signing_agents: Vec<AgentPubKey> = session_data.signing_agents().cloned().collect();
+    assert_eq!(
+        signing_agents,
+        vec![alice.agent_pubkey().clone(), bobbo.agent_pubkey().clone()]
+    );
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[1]
+        .call(
+            &bob_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(progress.assembled);
+}
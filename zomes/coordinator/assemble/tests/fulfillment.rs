@@ -0,0 +1,280 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
+
+mod common;
+use common::{create_call_to_action, create_commitment, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_to_action_assembles_once_its_fulfillment_policy_is_met() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction with three needs that assembles when any two are satisfied
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let need = sample.needs[0].clone();
+    let call_to_action = CallToAction {
+        needs: vec![need.clone(), need.clone(), need],
+        fulfillment_policy: FulfillmentPolicy::AnyNeeds { count: 2 },
+        ..sample
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob satisfies the first need
+    let commitment = Commitment {
+        call_to_action_hash: call_to_action_hash.clone(),
+        amount: 4,
+        comment: None,
+        need_index: 0,
+        call_to_action_version_hash: None,
+    };
+    create_commitment(&conductors[1], &bob_zome, commitment.clone()).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash.clone(),
+        )
+        .await;
+    assert!(progress.needs[0].satisfied);
+    assert!(!progress.assembled);
+
+    // Bob satisfies the third need, which is enough to assemble
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            need_index: 2,
+            ..commitment
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(!progress.needs[1].satisfied);
+    assert!(progress.assembled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unreachable_fulfillment_policy_is_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    // The weights don't match the needs
+    let call_to_action = CallToAction {
+        fulfillment_policy: FulfillmentPolicy::WeightedThreshold {
+            weights: vec![1, 1],
+            threshold: 1,
+        },
+        ..sample_call_to_action_2(&conductors[0], &alice_zome).await
+    };
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(&alice_zome, "create_call_to_action", call_to_action)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn optional_needs_are_only_satisfied_when_the_policy_counts_them() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction with a required need and an optional one
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let need = sample.needs[0].clone();
+    let call_to_action = CallToAction {
+        needs: vec![
+            need.clone(),
+            Need {
+                min_necessary: 0,
+                ..need
+            },
+        ],
+        ..sample
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action.clone()).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+    let record = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            fulfillment_policy: FulfillmentPolicy::AllRequiredAndSomeOptionalNeeds {
+                optional_count: 1,
+            },
+            ..call_to_action
+        },
+    )
+    .await;
+    let counting_call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob contributes to the optional need of both
+    for hash in [
+        call_to_action_hash.clone(),
+        counting_call_to_action_hash.clone(),
+    ] {
+        create_commitment(
+            &conductors[1],
+            &bob_zome,
+            Commitment {
+                call_to_action_hash: hash,
+                amount: 1,
+                comment: None,
+                need_index: 1,
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    }
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // AllRequiredNeeds doesn't count optional needs, so no satisfaction is created for it
+    let satisfactions: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_satisfactions_for_call_to_action",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(satisfactions.is_empty());
+
+    let satisfactions: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_satisfactions_for_call_to_action",
+            counting_call_to_action_hash,
+        )
+        .await;
+    assert_eq!(satisfactions.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn assemblies_meet_the_fulfillment_policy_of_the_latest_version() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction with two required needs
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let need = sample.needs[0].clone();
+    let call_to_action = CallToAction {
+        needs: vec![need.clone(), need],
+        ..sample
+    };
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action.clone()).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    // Alice loosens its policy so that any of them is enough
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: call_to_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            fulfillment_policy: FulfillmentPolicy::AnyNeeds { count: 1 },
+            ..call_to_action
+        },
+    };
+    let _update_record: Record = conductors[0]
+        .call(&alice_zome, "update_call_to_action", input)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob satisfies the first need, and the assembly references the update with the looser policy
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            call_to_action_hash: call_to_action_hash.clone(),
+            amount: 4,
+            comment: None,
+            need_index: 0,
+            call_to_action_version_hash: None,
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+    assert!(!progress.needs[1].satisfied);
+    assert!(progress.assembled);
+}
@@ -0,0 +1,375 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+use hc_zome_assemble_coordinator::index::{GetCallsToActionExpiringBetweenInput, Pagination};
+
+mod common;
+use common::{
+    create_call_to_action, create_commitment, sample_call_to_action_1, sample_call_to_action_2,
+    sample_full_commitment_2,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn new_calls_to_action_are_listed_as_open() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates two CallToActions
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+    let record_1 = create_call_to_action(&conductors[0], &alice_zome, sample_1).await;
+    let sample_2 = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record_2 = create_call_to_action(&conductors[0], &alice_zome, sample_2).await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let open_links: Vec<Link> = conductors[1]
+        .call(&bob_zome, "get_open_calls_to_action", None::<Pagination>)
+        .await;
+    assert_eq!(open_links.len(), 2);
+
+    let closed_links: Vec<Link> = conductors[1]
+        .call(&bob_zome, "get_closed_calls_to_action", None::<Pagination>)
+        .await;
+    assert_eq!(closed_links.len(), 0);
+
+    // Newest first
+    let first_page: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_open_calls_to_action",
+            Some(Pagination {
+                offset: 0,
+                limit: 1,
+            }),
+        )
+        .await;
+    assert_eq!(first_page.len(), 1);
+    assert_eq!(
+        first_page[0].target,
+        AnyLinkableHash::from(record_2.signed_action.action_address().clone())
+    );
+
+    let second_page: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_open_calls_to_action",
+            Some(Pagination {
+                offset: 1,
+                limit: 1,
+            }),
+        )
+        .await;
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(
+        second_page[0].target,
+        AnyLinkableHash::from(record_1.signed_action.action_address().clone())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_are_indexed_by_tag() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Tags must be lowercase
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_call_to_action",
+            CallToAction {
+                tags: vec!["Gardening".to_string()],
+                ..sample_1.clone()
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Alice creates a CallToAction tagged with "gardening"
+    let record = create_call_to_action(&conductors[0], &alice_zome, sample_1.clone()).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_by_tag",
+            "gardening".to_string(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target,
+        AnyLinkableHash::from(call_to_action_hash.clone())
+    );
+
+    // Alice changes its tag to "mutual-aid"
+    let input = UpdateCallToActionInput {
+        previous_call_to_action_hash: call_to_action_hash.clone(),
+        updated_call_to_action: CallToAction {
+            tags: vec!["mutual-aid".to_string()],
+            ..sample_1.clone()
+        },
+    };
+    let _update_record: Record = conductors[0]
+        .call(&alice_zome, "update_call_to_action", input)
+        .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_by_tag",
+            "gardening".to_string(),
+        )
+        .await;
+    assert!(links.is_empty());
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_by_tag",
+            "mutual-aid".to_string(),
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_are_indexed_by_expiration_day() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let now = Timestamp::now();
+    let in_one_hour = Timestamp::from_micros(now.as_micros() + 60 * 60 * 1_000_000);
+    let in_ten_days = Timestamp::from_micros(now.as_micros() + 10 * 24 * 60 * 60 * 1_000_000);
+
+    // Alice creates a CallToAction that expires in one hour and another one that expires in ten days
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+    let record_1 = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_one_hour),
+            ..sample_1.clone()
+        },
+    )
+    .await;
+    create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_ten_days),
+            ..sample_1
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_expiring_between",
+            GetCallsToActionExpiringBetweenInput {
+                start: now,
+                end: Timestamp::from_micros(now.as_micros() + 24 * 60 * 60 * 1_000_000),
+            },
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target,
+        AnyLinkableHash::from(record_1.signed_action.action_address().clone())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_assembled_by_other_agents_are_listed_as_closed() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+    create_call_to_action(&conductors[0], &alice_zome, sample_1).await;
+    let sample_2 = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record_2 = create_call_to_action(&conductors[0], &alice_zome, sample_2).await;
+    let call_to_action_hash = record_2.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob fulfills the second CallToAction, and can't delete the open index link that Alice created
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        sample_full_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone()))
+            .await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let open_links: Vec<Link> = conductors[0]
+        .call(&alice_zome, "get_open_calls_to_action", None::<Pagination>)
+        .await;
+    assert_eq!(open_links.len(), 1);
+    assert_ne!(
+        open_links[0].target,
+        AnyLinkableHash::from(call_to_action_hash.clone())
+    );
+
+    let closed_links: Vec<Link> = conductors[0]
+        .call(
+            &alice_zome,
+            "get_closed_calls_to_action",
+            None::<Pagination>,
+        )
+        .await;
+    assert_eq!(closed_links.len(), 1);
+    assert_eq!(
+        closed_links[0].target,
+        AnyLinkableHash::from(call_to_action_hash)
+    );
+
+    let all_links: Vec<Link> = conductors[0]
+        .call(&alice_zome, "get_all_calls_to_action", None::<Pagination>)
+        .await;
+    assert_eq!(all_links.len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn assembled_calls_to_action_are_not_listed_as_expiring() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let now = Timestamp::now();
+    let in_one_hour = Timestamp::from_micros(now.as_micros() + 60 * 60 * 1_000_000);
+
+    // Alice creates two CallToActions that expire in one hour
+    let sample_1 = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+    let record_1 = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_one_hour),
+            ..sample_1
+        },
+    )
+    .await;
+    let sample_2 = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record_2 = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_one_hour),
+            ..sample_2
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob fulfills the second one, so it's not closing soon anymore
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        sample_full_commitment_2(
+            &conductors[1],
+            &bob_zome,
+            Some(record_2.signed_action.action_address().clone()),
+        )
+        .await,
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_expiring_between",
+            GetCallsToActionExpiringBetweenInput {
+                start: now,
+                end: Timestamp::from_micros(now.as_micros() + 24 * 60 * 60 * 1_000_000),
+            },
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target,
+        AnyLinkableHash::from(record_1.signed_action.action_address().clone())
+    );
+}
@@ -0,0 +1,126 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::location::{BoundingBox, RadiusQuery};
+
+mod common;
+use common::{create_call_to_action, sample_call_to_action_1};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_can_be_found_by_location() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    let sample = sample_call_to_action_1(&conductors[0], &alice_zome).await;
+
+    // Out of range coordinates are rejected
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_call_to_action",
+            CallToAction {
+                location: Some(Location {
+                    latitude: 91.0,
+                    longitude: 2.1686,
+                    place_name: "Nowhere".to_string(),
+                }),
+                ..sample.clone()
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Alice creates a CallToAction in Barcelona and another one in Berlin
+    let barcelona_record = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            location: Some(Location {
+                latitude: 41.3874,
+                longitude: 2.1686,
+                place_name: "Barcelona".to_string(),
+            }),
+            ..sample.clone()
+        },
+    )
+    .await;
+    create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            location: Some(Location {
+                latitude: 52.52,
+                longitude: 13.405,
+                place_name: "Berlin".to_string(),
+            }),
+            ..sample.clone()
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_in_bounding_box",
+            BoundingBox {
+                min_latitude: 41.0,
+                min_longitude: 1.5,
+                max_latitude: 42.0,
+                max_longitude: 2.5,
+            },
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].target,
+        AnyLinkableHash::from(barcelona_record.signed_action.action_address().clone())
+    );
+
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_within_radius",
+            RadiusQuery {
+                latitude: 41.40,
+                longitude: 2.17,
+                radius_km: 10.0,
+            },
+        )
+        .await;
+    assert_eq!(links.len(), 1);
+
+    // Both cities are within 2000km of Barcelona
+    let links: Vec<Link> = conductors[1]
+        .call(
+            &bob_zome,
+            "get_calls_to_action_within_radius",
+            RadiusQuery {
+                latitude: 41.40,
+                longitude: 2.17,
+                radius_km: 2000.0,
+            },
+        )
+        .await;
+    assert_eq!(links.len(), 2);
+}
@@ -0,0 +1,149 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+
+mod common;
+use common::install_with_membrane_proof;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn progenitor_invites_new_members() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    let mut conductor = SweetConductor::from_config(ConductorConfig::default()).await;
+    let (alice, bobbo) = SweetAgents::two(conductor.keystore()).await;
+
+    // Alice is the progenitor of the DNA
+    let properties = DnaProperties {
+        progenitor: Some(alice.clone().into()),
+        ..Default::default()
+    };
+    let dna = dna.update_modifiers(
+        DnaModifiersOpt::none()
+            .with_properties(SerializedBytes::try_from(properties).unwrap().into()),
+    );
+
+    let app = conductor
+        .setup_app_for_agent("assemble_test", alice.clone(), &[dna.clone()])
+        .await
+        .unwrap();
+    let (alice_cell,) = app.into_tuple();
+    let alice_zome = alice_cell.zome("assemble");
+
+    let membrane_proof: SerializedBytes = conductor
+        .call(&alice_zome, "create_invitation", bobbo.clone())
+        .await;
+    let membrane_proof = AssembleMembraneProof::try_from(membrane_proof).unwrap();
+
+    assert_eq!(membrane_proof.invitations.len(), 1);
+    let signed_invitation = &membrane_proof.invitations[0];
+    assert_eq!(signed_invitation.inviter, alice);
+    assert_eq!(signed_invitation.invitation.invitee, bobbo);
+    assert_eq!(
+        signed_invitation.invitation.dna_hash,
+        dna.dna_hash().clone()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_invited_agents_can_join() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    let mut conductor = SweetConductor::from_config(ConductorConfig::default()).await;
+    let (alice, bobbo) = SweetAgents::two(conductor.keystore()).await;
+    let carol = SweetAgents::one(conductor.keystore()).await;
+
+    // Alice is the progenitor of the DNA
+    let properties = DnaProperties {
+        progenitor: Some(alice.clone().into()),
+        ..Default::default()
+    };
+    let dna = dna.update_modifiers(
+        DnaModifiersOpt::none()
+            .with_properties(SerializedBytes::try_from(properties).unwrap().into()),
+    );
+
+    let app = conductor
+        .setup_app_for_agent("assemble_test", alice.clone(), &[dna.clone()])
+        .await
+        .unwrap();
+    let (alice_cell,) = app.into_tuple();
+    let alice_zome = alice_cell.zome("assemble");
+
+    // Carol can't join without an invitation
+    assert!(
+        !install_with_membrane_proof(&conductor, "carol_without_proof", carol.clone(), &dna, None)
+            .await
+    );
+
+    let membrane_proof: SerializedBytes = conductor
+        .call(&alice_zome, "create_invitation", bobbo.clone())
+        .await;
+
+    // Carol can't reuse the invitation for Bob, nor forge one for them with Alice's signature
+    assert!(
+        !install_with_membrane_proof(
+            &conductor,
+            "carol_with_bob_proof",
+            carol.clone(),
+            &dna,
+            Some(membrane_proof.clone()),
+        )
+        .await
+    );
+    let mut forged_proof = AssembleMembraneProof::try_from(membrane_proof.clone()).unwrap();
+    forged_proof.invitations[0].invitation.invitee = carol.clone();
+    assert!(
+        !install_with_membrane_proof(
+            &conductor,
+            "carol_with_forged_proof",
+            carol.clone(),
+            &dna,
+            Some(SerializedBytes::try_from(forged_proof).unwrap()),
+        )
+        .await
+    );
+
+    // Bob joins with Alice's invitation, and can invite Carol in turn
+    assert!(
+        install_with_membrane_proof(
+            &conductor,
+            "bob_with_proof",
+            bobbo.clone(),
+            &dna,
+            Some(membrane_proof),
+        )
+        .await
+    );
+    let bob_cell = conductor
+        .get_sweet_cell(CellId::new(dna.dna_hash().clone(), bobbo.clone()))
+        .unwrap();
+    let bob_zome = bob_cell.zome("assemble");
+
+    let membrane_proof: SerializedBytes = conductor
+        .call(&bob_zome, "create_invitation", carol.clone())
+        .await;
+    assert!(
+        install_with_membrane_proof(
+            &conductor,
+            "carol_with_proof",
+            carol,
+            &dna,
+            Some(membrane_proof),
+        )
+        .await
+    );
+}
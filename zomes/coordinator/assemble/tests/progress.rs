@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::progress::CallToActionProgress;
+
+mod common;
+use common::{create_call_to_action, create_commitment, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn progress_aggregates_uncancelled_commitments() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction that needs at least 4
+    let call_to_action = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let record = create_call_to_action(&conductors[0], &alice_zome, call_to_action).await;
+    let call_to_action_hash = record.signed_action.action_address().clone();
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Alice and Bob commit 3 in total
+    let commitment = Commitment {
+        call_to_action_hash: call_to_action_hash.clone(),
+        amount: 2,
+        comment: None,
+        need_index: 0,
+        call_to_action_version_hash: None,
+    };
+    create_commitment(&conductors[0], &alice_zome, commitment.clone()).await;
+    create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            amount: 1,
+            ..commitment
+        },
+    )
+    .await;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    let progress: CallToActionProgress = conductors[0]
+        .call(
+            &alice_zome,
+            "get_call_to_action_progress",
+            call_to_action_hash,
+        )
+        .await;
+
+    assert_eq!(progress.needs.len(), 1);
+    assert_eq!(progress.needs[0].amount_contributed, 3);
+    assert_eq!(progress.needs[0].committers_count, 2);
+    assert!(!progress.needs[0].satisfied);
+    assert_eq!(progress.needs[0].satisfaction_hash, None);
+    assert!(!progress.assembled);
+}
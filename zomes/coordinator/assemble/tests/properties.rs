@@ -0,0 +1,117 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+
+use hdk::prelude::*;
+use holochain::test_utils::consistency_10s;
+use holochain::{conductor::config::ConductorConfig, sweettest::*};
+
+use assemble_integrity::*;
+use hc_zome_assemble_coordinator::call_to_action::UpdateCallToActionInput;
+
+mod common;
+use common::{create_call_to_action, install_with_membrane_proof, sample_call_to_action_2};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn calls_to_action_follow_the_dna_properties() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    let mut conductor = SweetConductor::from_config(ConductorConfig::default()).await;
+    let (alice, bobbo) = SweetAgents::two(conductor.keystore()).await;
+
+    // Only Alice, the progenitor, can create calls to action, and with at most 2 needs
+    let properties = DnaProperties {
+        progenitor: Some(alice.clone().into()),
+        max_needs: 2,
+        anyone_can_create_calls_to_action: false,
+        ..Default::default()
+    };
+    let dna = dna.update_modifiers(
+        DnaModifiersOpt::none().with_properties(
+            SerializedBytes::try_from(properties.clone())
+                .unwrap()
+                .into(),
+        ),
+    );
+
+    let app = conductor
+        .setup_app_for_agent("assemble_test", alice.clone(), &[dna.clone()])
+        .await
+        .unwrap();
+    let (alice_cell,) = app.into_tuple();
+    let alice_zome = alice_cell.zome("assemble");
+
+    let read_properties: DnaProperties =
+        conductor.call(&alice_zome, "get_dna_properties", ()).await;
+    assert_eq!(read_properties, properties);
+
+    let sample = sample_call_to_action_2(&conductor, &alice_zome).await;
+    let need = sample.needs[0].clone();
+    let result: Result<Record, _> = conductor
+        .call_fallible(
+            &alice_zome,
+            "create_call_to_action",
+            CallToAction {
+                needs: vec![need.clone(), need.clone(), need],
+                ..sample.clone()
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    let _record: Record = conductor
+        .call(&alice_zome, "create_call_to_action", sample.clone())
+        .await;
+
+    // Bob joins with Alice's invitation, but isn't the progenitor so they can't create calls to action
+    let membrane_proof: SerializedBytes = conductor
+        .call(&alice_zome, "create_invitation", bobbo.clone())
+        .await;
+    assert!(
+        install_with_membrane_proof(
+            &conductor,
+            "bob_with_proof",
+            bobbo.clone(),
+            &dna,
+            Some(membrane_proof),
+        )
+        .await
+    );
+    let bob_cell = conductor
+        .get_sweet_cell(CellId::new(dna.dna_hash().clone(), bobbo.clone()))
+        .unwrap();
+    let bob_zome = bob_cell.zome("assemble");
+
+    let result: Result<Record, _> = conductor
+        .call_fallible(&bob_zome, "create_call_to_action", sample.clone())
+        .await;
+    assert!(result.is_err());
+
+    // Bob can still update the calls to action that Alice made them an admin of
+    let call_to_action = CallToAction {
+        admins: vec![bobbo.clone()],
+        ..sample
+    };
+    let record = create_call_to_action(&conductor, &alice_zome, call_to_action.clone()).await;
+
+    consistency_10s([&alice_cell, &bob_cell]).await;
+
+    let record: Record = conductor
+        .call(
+            &bob_zome,
+            "update_call_to_action",
+            UpdateCallToActionInput {
+                previous_call_to_action_hash: record.signed_action.action_address().clone(),
+                updated_call_to_action: CallToAction {
+                    tags: vec![String::from("delegated")],
+                    ..call_to_action
+                },
+            },
+        )
+        .await;
+    assert_eq!(record.action().author(), &bobbo);
+}
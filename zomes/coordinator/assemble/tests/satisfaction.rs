@@ -146,3 +146,141 @@ async fn create_and_update_satisfaction() {
 
     assert_eq!(update_record, get_record.unwrap());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn satisfaction_with_not_enough_commitments_is_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (_bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        sample_call_to_action_1(&conductors[0], &alice_zome).await,
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    // Alice commits less than the min_necessary for the need
+    let commitment = Commitment {
+        amount: 1,
+        ..sample_commitment_1(
+            &conductors[0],
+            &alice_zome,
+            Some(call_to_action_hash.clone()),
+        )
+        .await
+    };
+    let commitment_hash = create_commitment(&conductors[0], &alice_zome, commitment)
+        .await
+        .signed_action
+        .hashed
+        .hash;
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_satisfaction",
+            Satisfaction {
+                call_to_action_hash,
+                need_index: 0,
+                commitments_hashes: vec![commitment_hash],
+                approvals_hashes: vec![],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn satisfactions_for_expired_calls_to_action_are_rejected() {
+    // Use prebuilt dna file
+    let dna_path = std::env::current_dir()
+        .unwrap()
+        .join("../../../workdir/assemble_test.dna");
+    let dna = SweetDnaFile::from_bundle(&dna_path).await.unwrap();
+
+    // Set up conductors
+    let mut conductors = SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let apps = conductors.setup_app("assemble_test", &[dna]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let alice_zome = alice.zome("assemble");
+    let bob_zome = bobbo.zome("assemble");
+
+    // Alice creates a CallToAction that expires in a few seconds, with a need that requires their approval
+    let sample = sample_call_to_action_2(&conductors[0], &alice_zome).await;
+    let in_three_seconds = Timestamp::from_micros(Timestamp::now().as_micros() + 3_000_000);
+    let call_to_action_hash = create_call_to_action(
+        &conductors[0],
+        &alice_zome,
+        CallToAction {
+            expiration_time: Some(in_three_seconds),
+            needs: vec![Need {
+                requires_admin_approval: true,
+                ..sample.needs[0].clone()
+            }],
+            ..sample
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Bob commits enough to satisfy the need before it expires
+    let commitment_hash = create_commitment(
+        &conductors[1],
+        &bob_zome,
+        Commitment {
+            amount: 4,
+            ..sample_commitment_2(&conductors[1], &bob_zome, Some(call_to_action_hash.clone()))
+                .await
+        },
+    )
+    .await
+    .signed_action
+    .hashed
+    .hash;
+
+    tokio::time::sleep(std::time::Duration::from_secs(4)).await;
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Alice approves the commitment only after the call to action has expired
+    let approval: Record = conductors[0]
+        .call(&alice_zome, "approve_commitment", commitment_hash.clone())
+        .await;
+
+    let result: Result<Record, _> = conductors[0]
+        .call_fallible(
+            &alice_zome,
+            "create_satisfaction",
+            Satisfaction {
+                call_to_action_hash,
+                need_index: 0,
+                commitments_hashes: vec![commitment_hash],
+                approvals_hashes: vec![approval.signed_action.hashed.hash],
+                call_to_action_version_hash: None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
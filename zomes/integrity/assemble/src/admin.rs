@@ -0,0 +1,240 @@
+use hdi::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::{get_call_to_action_author, CallToAction};
+
+/// A change in who owns or administers a call to action, recorded in its admin history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AdminChange {
+    AdminAdded {
+        admin: AgentPubKey,
+    },
+    AdminRemoved {
+        admin: AgentPubKey,
+    },
+    OwnershipTransferred {
+        previous_owner: AgentPubKey,
+        new_owner: AgentPubKey,
+    },
+}
+
+/// Tag of the CallToActionToAdminChanges links, so that the admin history can be read without getting every update
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct AdminChangesLinkTag {
+    pub changes: Vec<AdminChange>,
+}
+
+impl TryFrom<AdminChangesLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: AdminChangesLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for AdminChangesLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<AdminChangesLinkTag> {
+        AdminChangesLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+/// The author owns the call to action until they transfer its ownership
+pub fn get_call_to_action_owner(
+    author: &AgentPubKey,
+    call_to_action: &CallToAction,
+) -> AgentPubKey {
+    call_to_action.owner.clone().unwrap_or(author.clone())
+}
+
+/// The changes in the owner and the admins between two versions of a call to action
+pub fn get_admin_changes(
+    author: &AgentPubKey,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+) -> Vec<AdminChange> {
+    let mut changes: Vec<AdminChange> = vec![];
+
+    let previous_owner = get_call_to_action_owner(author, previous_call_to_action);
+    let new_owner = get_call_to_action_owner(author, updated_call_to_action);
+    if previous_owner.ne(&new_owner) {
+        changes.push(AdminChange::OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+    }
+
+    let previous_admins: BTreeSet<&AgentPubKey> = previous_call_to_action.admins.iter().collect();
+    let updated_admins: BTreeSet<&AgentPubKey> = updated_call_to_action.admins.iter().collect();
+    for admin in updated_admins.difference(&previous_admins) {
+        changes.push(AdminChange::AdminAdded {
+            admin: (*admin).clone(),
+        });
+    }
+    for admin in previous_admins.difference(&updated_admins) {
+        changes.push(AdminChange::AdminRemoved {
+            admin: (*admin).clone(),
+        });
+    }
+
+    changes
+}
+
+/// Whether the admins and the owner have to approve commitments or countersign the assembly of the call to action
+pub fn requires_admins_agreement(call_to_action: &CallToAction) -> bool {
+    call_to_action.countersigned_assembly
+        || call_to_action
+            .needs
+            .iter()
+            .any(|need| need.requires_admin_approval)
+}
+
+/// Only the owner can transfer the ownership, and the last admin of a call to action can't be removed
+///
+/// The owner is not counted as an admin: a call to action that has admins must keep at least one of them,
+/// while one that never had any admins is only administered by its owner.
+/// Approvals and countersigned assemblies are validated against the admins of the versions they rely on,
+/// so the owner and the admins can't change while they have to approve commitments or countersign the assembly.
+///
+/// Whether the agent updating the call to action is its owner or one of its admins is checked for every update
+pub fn validate_admin_changes(
+    author: &AgentPubKey,
+    previous_call_to_action: &CallToAction,
+    updated_call_to_action: &CallToAction,
+    updater: &AgentPubKey,
+) -> ValidateCallbackResult {
+    let changes = get_admin_changes(author, previous_call_to_action, updated_call_to_action);
+    if changes.is_empty() {
+        return ValidateCallbackResult::Valid;
+    }
+
+    if requires_admins_agreement(previous_call_to_action)
+        || requires_admins_agreement(updated_call_to_action)
+    {
+        return ValidateCallbackResult::Invalid(String::from(
+            "The owner and the admins of a call to action can't change while they have to approve its commitments or countersign its assembly",
+        ));
+    }
+
+    let previous_owner = get_call_to_action_owner(author, previous_call_to_action);
+    let new_owner = get_call_to_action_owner(author, updated_call_to_action);
+    if previous_owner.ne(&new_owner) && previous_owner.ne(updater) {
+        return ValidateCallbackResult::Invalid(String::from(
+            "Only the owner of a call to action can transfer its ownership",
+        ));
+    }
+
+    let admins: BTreeSet<&AgentPubKey> = updated_call_to_action.admins.iter().collect();
+    if admins.len() != updated_call_to_action.admins.len() {
+        return ValidateCallbackResult::Invalid(String::from(
+            "A call to action can't have the same admin twice",
+        ));
+    }
+    if admins.contains(&new_owner) {
+        return ValidateCallbackResult::Invalid(String::from(
+            "The owner of a call to action can't also be one of its admins",
+        ));
+    }
+    if !previous_call_to_action.admins.is_empty() && admins.is_empty() {
+        return ValidateCallbackResult::Invalid(String::from(
+            "The last admin of a call to action can't be removed",
+        ));
+    }
+
+    ValidateCallbackResult::Valid
+}
+
+/// Follows the updates of a call to action back to the action that created it
+pub fn get_original_call_to_action_hash(update: &Update) -> ExternResult<ActionHash> {
+    let mut action_hash = update.original_action_address.clone();
+    loop {
+        let action = must_get_action(action_hash.clone())?;
+        match action.hashed.content {
+            Action::Create(_) => return Ok(action_hash),
+            Action::Update(update) => {
+                action_hash = update.original_action_address;
+            }
+            _ => {
+                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "The original action of a call to action must be a Create or an Update"
+                ))))
+            }
+        }
+    }
+}
+
+pub fn validate_create_link_call_to_action_to_admin_changes(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let call_to_action_hash =
+        ActionHash::try_from(base_address).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let updated_call_to_action: crate::CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    let Action::Update(update) = record.action().clone() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Admin changes can only link to updates of the call to action",
+        )));
+    };
+    if get_original_call_to_action_hash(&update)?.ne(&call_to_action_hash) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A call to action can only link to its own admin changes",
+        )));
+    }
+    if update.author.ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an update can link to its admin changes",
+        )));
+    }
+
+    let record = must_get_valid_record(update.original_action_address.clone())?;
+    let previous_call_to_action: crate::CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Dependant action must be accompanied by an entry"
+        ))))?;
+    let author = get_call_to_action_author(&EntryCreationAction::Update(update))?;
+
+    let Ok(admin_changes_link_tag) = AdminChangesLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Malformed tag for the admin changes",
+        )));
+    };
+    if admin_changes_link_tag.changes.is_empty()
+        || admin_changes_link_tag.changes.ne(&get_admin_changes(
+            &author,
+            &previous_call_to_action,
+            &updated_call_to_action,
+        ))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag doesn't match the admin changes of the update",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_call_to_action_to_admin_changes(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "CallToActionToAdminChanges links cannot be deleted",
+    )))
+}
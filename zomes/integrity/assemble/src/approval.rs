@@ -0,0 +1,206 @@
+use hdi::prelude::*;
+
+/// An admin of the call to action accepting a commitment for a need that requires admin approval
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CommitmentApproval {
+    pub commitment_hash: ActionHash,
+}
+
+/// An admin of the call to action turning down a commitment for a need that requires admin approval
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CommitmentRejection {
+    pub commitment_hash: ActionHash,
+    pub reason: String,
+}
+
+pub const MAX_REJECTION_REASON_LENGTH: usize = 1024;
+
+/// Only the owner or the admins of the call to action can review the commitments for its needs that require admin approval
+fn validate_commitment_reviewer(
+    reviewer: &AgentPubKey,
+    commitment_hash: ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(commitment_hash)?;
+    let commitment: crate::Commitment = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Dependant action must be accompanied by an entry"
+        ))))?;
+    // The admins can't change while a need requires their approval, so the version the commitment references has the current ones
+    let Some((author, call_to_action)) = crate::must_get_call_to_action_version(
+        &commitment.call_to_action_hash,
+        &commitment.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The call_to_action_version_hash of this commitment is not a version of its call to action",
+        )));
+    };
+    let Some(need) = call_to_action.needs.get(commitment.need_index as usize) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The need_index of this commitment does not exist in its call to action",
+        )));
+    };
+    if !need.requires_admin_approval {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only commitments for needs that require admin approval can be approved or rejected",
+        )));
+    }
+    if !crate::is_author_or_admin(&author, &call_to_action, reviewer) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the admins for this call to action can approve or reject its commitments",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_commitment_approval(
+    action: EntryCreationAction,
+    commitment_approval: CommitmentApproval,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_commitment_reviewer(action.author(), commitment_approval.commitment_hash)
+}
+pub fn validate_update_commitment_approval(
+    _action: Update,
+    _commitment_approval: CommitmentApproval,
+    _original_action: EntryCreationAction,
+    _original_commitment_approval: CommitmentApproval,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Commitment approvals cannot be updated",
+    )))
+}
+pub fn validate_delete_commitment_approval(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_commitment_approval: CommitmentApproval,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Commitment approvals cannot be deleted",
+    )))
+}
+
+pub fn validate_create_commitment_rejection(
+    action: EntryCreationAction,
+    commitment_rejection: CommitmentRejection,
+) -> ExternResult<ValidateCallbackResult> {
+    if commitment_rejection.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A commitment rejection must have a reason",
+        )));
+    }
+    if commitment_rejection.reason.chars().count() > MAX_REJECTION_REASON_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "The reason of a commitment rejection can't be longer than {} characters",
+            MAX_REJECTION_REASON_LENGTH
+        )));
+    }
+    validate_commitment_reviewer(action.author(), commitment_rejection.commitment_hash)
+}
+pub fn validate_update_commitment_rejection(
+    _action: Update,
+    _commitment_rejection: CommitmentRejection,
+    _original_action: EntryCreationAction,
+    _original_commitment_rejection: CommitmentRejection,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Commitment rejections cannot be updated",
+    )))
+}
+pub fn validate_delete_commitment_rejection(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_commitment_rejection: CommitmentRejection,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Commitment rejections cannot be deleted",
+    )))
+}
+
+pub fn validate_create_link_commitment_to_approvals(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let commitment_hash =
+        ActionHash::try_from(base_address).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let commitment_approval: crate::CommitmentApproval = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if commitment_approval.commitment_hash.ne(&commitment_hash) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A commitment can only link to its own approvals",
+        )));
+    }
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an approval can link to it from its commitment",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_commitment_to_approvals(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "CommitmentToApprovals links cannot be deleted",
+    )))
+}
+
+pub fn validate_create_link_commitment_to_rejections(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let commitment_hash =
+        ActionHash::try_from(base_address).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let commitment_rejection: crate::CommitmentRejection = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if commitment_rejection.commitment_hash.ne(&commitment_hash) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A commitment can only link to its own rejections",
+        )));
+    }
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a rejection can link to it from its commitment",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_commitment_to_rejections(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "CommitmentToRejections links cannot be deleted",
+    )))
+}
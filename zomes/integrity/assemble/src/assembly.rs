@@ -1,31 +1,169 @@
 use hdi::prelude::*;
+use std::collections::BTreeSet;
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct Assembly {
     pub call_to_action_hash: ActionHash,
     pub satisfactions_hashes: Vec<ActionHash>,
+    #[serde(default)]
+    pub children_assemblies_hashes: Vec<ActionHash>,
+    /// The update of the call to action whose policies the assembly meets, if it's not the call to action as it was created
+    #[serde(default)]
+    pub call_to_action_version_hash: Option<ActionHash>,
 }
 pub fn validate_create_assembly(
     _action: EntryCreationAction,
     assembly: Assembly,
 ) -> ExternResult<ValidateCallbackResult> {
-    let record = must_get_valid_record(assembly.call_to_action_hash.clone())?;
-    let _call_to_action: crate::CallToAction = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Dependant action must be accompanied by an entry"
-        ))))?;
+    // The children required by the SomeChildren policy only exist after their parent,
+    // so they are set in an update that the assembly has to reference
+    let Some((_author, call_to_action)) = crate::must_get_call_to_action_version(
+        &assembly.call_to_action_hash,
+        &assembly.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The call_to_action_version_hash of this assembly is not a version of its call to action",
+        )));
+    };
+    let mut satisfied_needs: BTreeSet<u32> = BTreeSet::new();
     for action_hash in assembly.satisfactions_hashes.clone() {
         let record = must_get_valid_record(action_hash)?;
-        let _satisfaction: crate::Satisfaction = record
+        let satisfaction: crate::Satisfaction = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Dependant action must be accompanied by an entry"
+            ))))?;
+        if satisfaction
+            .call_to_action_hash
+            .ne(&assembly.call_to_action_hash)
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "All the satisfactions of an assembly must be for its call to action",
+            )));
+        }
+        if !satisfied_needs.insert(satisfaction.need_index) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "An assembly can't reference two satisfactions for the same need",
+            )));
+        }
+    }
+    if !crate::is_fulfillment_policy_met(&call_to_action, &satisfied_needs) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The satisfactions of an assembly must meet the fulfillment policy of its call to action",
+        )));
+    }
+    let mut assembled_children: BTreeSet<ActionHash> = BTreeSet::new();
+    for action_hash in assembly.children_assemblies_hashes.clone() {
+        let record = must_get_valid_record(action_hash)?;
+        let child_assembly: crate::Assembly = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Dependant action must be accompanied by an entry"
+            ))))?;
+        let record = must_get_valid_record(child_assembly.call_to_action_hash.clone())?;
+        let child_call_to_action: crate::CallToAction = record
             .entry()
             .to_app_option()
             .map_err(|e| wasm_error!(e))?
             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
                 "Dependant action must be accompanied by an entry"
             ))))?;
+        if child_call_to_action
+            .parent_call_to_action_hash
+            .ne(&Some(assembly.call_to_action_hash.clone()))
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "The children assemblies of an assembly must be for children of its call to action",
+            )));
+        }
+        if !assembled_children.insert(child_assembly.call_to_action_hash) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "An assembly can't reference two assemblies for the same child",
+            )));
+        }
+    }
+    // Which children exist or have been cancelled can't be checked deterministically,
+    // the coordinator zome checks it for the AllChildren policy
+    if let crate::ChildrenPolicy::SomeChildren { children_hashes } = call_to_action.children_policy
+    {
+        if children_hashes
+            .iter()
+            .any(|child_hash| !assembled_children.contains(child_hash))
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "An assembly must reference an assembly for every required child of its call to action",
+            )));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// Assemblies for calls to action that require countersigning must be countersigned by their author and all their admins
+///
+/// The countersigning session is only available in the entry itself, so this is checked on the whole op
+pub fn validate_assembly_countersigning(op: &Op) -> ExternResult<ValidateCallbackResult> {
+    let (action, entry) = match op {
+        Op::StoreEntry(StoreEntry { action, entry }) => (action.hashed.content.clone(), entry),
+        Op::StoreRecord(StoreRecord { record }) => {
+            let Some(entry) = record.entry().as_option() else {
+                return Ok(ValidateCallbackResult::Valid);
+            };
+            match record.action() {
+                Action::Create(create) => (EntryCreationAction::Create(create.clone()), entry),
+                Action::Update(update) => (EntryCreationAction::Update(update.clone()), entry),
+                _ => return Ok(ValidateCallbackResult::Valid),
+            }
+        }
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let assembly_entry_type: EntryType = crate::UnitEntryTypes::Assembly.try_into()?;
+    if action.entry_type().ne(&assembly_entry_type) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let (signing_agents, app_entry) = match entry {
+        Entry::CounterSign(session_data, app_entry) => (
+            Some(
+                session_data
+                    .signing_agents()
+                    .cloned()
+                    .collect::<BTreeSet<AgentPubKey>>(),
+            ),
+            app_entry.as_ref().clone(),
+        ),
+        Entry::App(app_entry) => (None, app_entry.clone()),
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let assembly = Assembly::try_from(app_entry.into_sb()).map_err(|e| wasm_error!(e))?;
+
+    let Some((author, call_to_action)) = crate::must_get_call_to_action_version(
+        &assembly.call_to_action_hash,
+        &assembly.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The call_to_action_version_hash of this assembly is not a version of its call to action",
+        )));
+    };
+    if !call_to_action.countersigned_assembly {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let Some(signing_agents) = signing_agents else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The assembly for this call to action must be countersigned",
+        )));
+    };
+    // The owner and the admins can't change while the assembly has to be countersigned
+    let mut required_agents: BTreeSet<AgentPubKey> =
+        call_to_action.admins.iter().cloned().collect();
+    required_agents.insert(crate::get_call_to_action_owner(&author, &call_to_action));
+    if !required_agents.is_subset(&signing_agents) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The assembly for this call to action must be countersigned by its owner and all its admins",
+        )));
     }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -40,13 +178,62 @@ pub fn validate_update_assembly(
     )))
 }
 pub fn validate_delete_assembly(
-    _action: Delete,
-    _original_action: EntryCreationAction,
-    _original_assembly: Assembly,
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_assembly: Assembly,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Assemblies cannot be deleted",
-    )))
+    if !can_delete_assembly(original_action.author(), &original_assembly, &action.author)? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents involved in an assembly can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// Assemblies are deleted when they are invalidated, which only the agents involved in them
+/// and the owner and the admins of their call to action can do
+pub fn can_delete_assembly(
+    assembly_author: &AgentPubKey,
+    assembly: &Assembly,
+    agent: &AgentPubKey,
+) -> ExternResult<bool> {
+    let record = must_get_valid_record(assembly.call_to_action_hash.clone())?;
+    let call_to_action: crate::CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Dependant action must be accompanied by an entry"
+        ))))?;
+    if crate::is_author_or_admin(record.action().author(), &call_to_action, agent) {
+        return Ok(true);
+    }
+    Ok(get_assembly_agents(assembly_author, assembly)?.contains(agent))
+}
+/// The links to an assembly can be deleted by their author or by the agents that can delete the assembly
+fn validate_delete_link_to_assembly(
+    action: &DeleteLink,
+    original_action: &CreateLink,
+    target: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action.author.eq(&action.author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let action_hash =
+        ActionHash::try_from(target).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let assembly: crate::Assembly = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if !can_delete_assembly(record.action().author(), &assembly, &action.author)? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents that can delete an assembly can delete the links to it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_create_link_call_to_action_to_assemblies(
     _action: CreateLink,
@@ -77,15 +264,13 @@ pub fn validate_create_link_call_to_action_to_assemblies(
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_call_to_action_to_assemblies(
-    _action: DeleteLink,
-    _original_action: CreateLink,
+    action: DeleteLink,
+    original_action: CreateLink,
     _base: AnyLinkableHash,
-    _target: AnyLinkableHash,
+    target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "CallToActionToAssemblies links cannot be deleted",
-    )))
+    validate_delete_link_to_assembly(&action, &original_action, target)
 }
 pub fn validate_create_link_satisfaction_to_assemblies(
     _action: CreateLink,
@@ -116,13 +301,82 @@ pub fn validate_create_link_satisfaction_to_assemblies(
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_satisfaction_to_assemblies(
-    _action: DeleteLink,
-    _original_action: CreateLink,
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_to_assembly(&action, &original_action, target)
+}
+
+/// Returns the author of the assembly and the agents involved in its satisfactions
+pub fn get_assembly_agents(
+    author: &AgentPubKey,
+    assembly: &Assembly,
+) -> ExternResult<BTreeSet<AgentPubKey>> {
+    let mut agents: BTreeSet<AgentPubKey> = BTreeSet::new();
+    agents.insert(author.clone());
+    for satisfaction_hash in assembly.satisfactions_hashes.clone() {
+        let record = must_get_valid_record(satisfaction_hash)?;
+        let satisfaction: crate::Satisfaction = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Dependant action must be accompanied by an entry"
+            ))))?;
+        agents.extend(crate::get_satisfaction_agents(
+            record.action().author(),
+            &satisfaction,
+        )?);
+    }
+    Ok(agents)
+}
+pub fn validate_create_link_agent_to_assemblies(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let agent = base_address
+        .into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The base of the link must be an agent"
+        ))))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let assembly: crate::Assembly = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an assembly can link to it from the agents involved in it",
+        )));
+    }
+    if !get_assembly_agents(record.action().author(), &assembly)?.contains(&agent) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Can't link to an assembly from an agent that is not involved in it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_agent_to_assemblies(
+    action: DeleteLink,
+    original_action: CreateLink,
     _base: AnyLinkableHash,
     _target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "SatisfactionToAssemblies links cannot be deleted",
-    )))
+    if action.author.ne(&original_action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an AgentToAssemblies link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
 }
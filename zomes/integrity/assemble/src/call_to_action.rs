@@ -1,4 +1,5 @@
 use hdi::prelude::*;
+use std::collections::BTreeSet;
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -7,6 +8,48 @@ pub struct CallToAction {
     pub parent_call_to_action_hash: Option<ActionHash>,
     pub expiration_time: Option<Timestamp>,
     pub needs: Vec<Need>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub location: Option<crate::Location>,
+    #[serde(default)]
+    pub children_policy: ChildrenPolicy,
+    #[serde(default)]
+    pub fulfillment_policy: FulfillmentPolicy,
+    /// Whether the assembly must be countersigned by the author and all the admins
+    #[serde(default)]
+    pub countersigned_assembly: bool,
+    /// The agent that the author transferred the ownership to, if any
+    #[serde(default)]
+    pub owner: Option<AgentPubKey>,
+}
+
+/// Which of its children have to assemble before the call to action can assemble
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(tag = "type")]
+pub enum ChildrenPolicy {
+    /// The call to action assembles regardless of its children
+    #[default]
+    Independent,
+    /// Every child that hasn't been cancelled has to assemble
+    AllChildren,
+    /// Only the given children have to assemble
+    SomeChildren { children_hashes: Vec<ActionHash> },
+}
+
+/// Which of its satisfied needs are enough for the call to action to assemble
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(tag = "type")]
+pub enum FulfillmentPolicy {
+    /// Every need with a min_necessary greater than 0 has to be satisfied
+    #[default]
+    AllRequiredNeeds,
+    /// Any `count` needs have to be satisfied, whether they are required or optional
+    AnyNeeds { count: u32 },
+    /// Every required need and at least `optional_count` optional needs have to be satisfied
+    AllRequiredAndSomeOptionalNeeds { optional_count: u32 },
+    /// The weights of the satisfied needs, one for each need, have to add up to the threshold
+    WeightedThreshold { weights: Vec<u32>, threshold: u32 },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -15,55 +58,530 @@ pub struct Need {
     pub max_possible: Option<u32>,
     pub description: String,
     pub requires_admin_approval: bool,
+    /// How many of the author and admins must approve each commitment, if the need requires admin approval
+    #[serde(default = "default_approval_threshold")]
+    pub approval_threshold: u32,
 }
 
+fn default_approval_threshold() -> u32 {
+    1
+}
+
+impl Need {
+    /// Optional needs are satisfied by any contribution, so that fulfillment policies can count them
+    pub fn amount_to_satisfy(&self) -> u32 {
+        self.min_necessary.max(1)
+    }
+}
+
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 32;
+
 pub fn validate_create_call_to_action(
-    _action: EntryCreationAction,
+    action: EntryCreationAction,
     call_to_action: CallToAction,
 ) -> ExternResult<ValidateCallbackResult> {
-    if let Some(action_hash) = call_to_action.parent_call_to_action_hash.clone() {
+    // Updates also run through this function, and validate_update_call_to_action checks who can make them
+    let is_create = matches!(action, EntryCreationAction::Create(_));
+    let properties = crate::DnaProperties::get()?;
+    if is_create
+        && !properties.anyone_can_create_calls_to_action
+        && properties.progenitor().ne(&Some(action.author().clone()))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the progenitor can create calls to action in this DNA",
+        )));
+    }
+    if let ValidateCallbackResult::Invalid(reason) =
+        validate_dna_properties_limits(&call_to_action, action.timestamp(), &properties)
+    {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if let ValidateCallbackResult::Invalid(reason) = validate_tags(&call_to_action.tags) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if let Some(location) = &call_to_action.location {
+        if let ValidateCallbackResult::Invalid(reason) = crate::validate_location(location) {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    }
+    if let ValidateCallbackResult::Invalid(reason) = validate_fulfillment_policy(&call_to_action) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    // The author of an update may not be the author of the call to action, so validate_update_call_to_action checks them
+    if is_create {
+        if let ValidateCallbackResult::Invalid(reason) =
+            validate_approval_thresholds(action.author(), &call_to_action)
+        {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    }
+    // Ownership transfers in updates are checked by validate_admin_changes
+    if is_create && call_to_action.owner.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A call to action is owned by its author when it's created",
+        )));
+    }
+    // The parent can't change in updates, so it's only checked when the child is created
+    if let Some(action_hash) = call_to_action
+        .parent_call_to_action_hash
+        .clone()
+        .filter(|_| is_create)
+    {
         let record = must_get_valid_record(action_hash)?;
-        let _call_to_action: crate::CallToAction = record
+        let parent_call_to_action: crate::CallToAction = record
             .entry()
             .to_app_option()
             .map_err(|e| wasm_error!(e))?
             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
                 "Dependant action must be accompanied by an entry"
             ))))?;
+        // Children can block the assembly of their parent, so only its owner and its admins can add them
+        if !is_author_or_admin(
+            record.action().author(),
+            &parent_call_to_action,
+            action.author(),
+        ) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Only the owner or the admins of a call to action can create children for it",
+            )));
+        }
     }
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_update_call_to_action(
-    _action: Update,
-    _call_to_action: CallToAction,
-    _original_action: EntryCreationAction,
-    _original_call_to_action: CallToAction,
+    action: Update,
+    call_to_action: CallToAction,
+    original_action: EntryCreationAction,
+    original_call_to_action: CallToAction,
 ) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_dna_properties_limits(
+        &call_to_action,
+        &action.timestamp,
+        &crate::DnaProperties::get()?,
+    ) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if let ValidateCallbackResult::Invalid(reason) = validate_tags(&call_to_action.tags) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if let Some(location) = &call_to_action.location {
+        if let ValidateCallbackResult::Invalid(reason) = crate::validate_location(location) {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    }
+    if let ValidateCallbackResult::Invalid(reason) = validate_fulfillment_policy(&call_to_action) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    let author = get_call_to_action_author(&original_action)?;
+    if let ValidateCallbackResult::Invalid(reason) =
+        validate_approval_thresholds(&author, &call_to_action)
+    {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if !is_author_or_admin(&author, &original_call_to_action, &action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can update it",
+        )));
+    }
+    if let ValidateCallbackResult::Invalid(reason) = crate::validate_admin_changes(
+        &author,
+        &original_call_to_action,
+        &call_to_action,
+        &action.author,
+    ) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if call_to_action.parent_call_to_action_hash
+        != original_call_to_action.parent_call_to_action_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The parent of a call to action can't be changed",
+        )));
+    }
+    // The admins can't change while they have to agree, which only holds if these settings don't change either
+    if call_to_action.countersigned_assembly != original_call_to_action.countersigned_assembly {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Whether the assembly of a call to action is countersigned can't be changed",
+        )));
+    }
+    let approval_settings_changed = original_call_to_action
+        .needs
+        .iter()
+        .zip(call_to_action.needs.iter())
+        .any(|(previous_need, need)| {
+            previous_need.requires_admin_approval != need.requires_admin_approval
+                || previous_need.approval_threshold != need.approval_threshold
+        });
+    if approval_settings_changed {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Whether a need requires admin approval and its approval threshold can't be changed",
+        )));
+    }
+    // Commitments and satisfactions can reference older versions of the call to action, so its expiration
+    // can only be postponed for the latest version to be the one that decides when it expires
+    let expiration_brought_forward = match (
+        original_call_to_action.expiration_time,
+        call_to_action.expiration_time,
+    ) {
+        (Some(previous_expiration_time), Some(expiration_time)) => {
+            expiration_time < previous_expiration_time
+        }
+        (None, Some(_)) => true,
+        _ => false,
+    };
+    if expiration_brought_forward {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The expiration time of a call to action can be postponed but not brought forward",
+        )));
+    }
+    // Needs can change freely here since commitments and satisfactions are validated against the version
+    // of the call to action they reference, so an update can't change the need they were made for.
+    // The coordinator zome keeps the needs that already have commitments at their index so that they
+    // still mean the same need in the latest version
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_call_to_action(
-    _action: Delete,
-    _original_action: EntryCreationAction,
-    _original_call_to_action: CallToAction,
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_call_to_action: CallToAction,
 ) -> ExternResult<ValidateCallbackResult> {
+    let author = get_call_to_action_author(&original_action)?;
+    if !is_author_or_admin(&author, &original_call_to_action, &action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can delete it",
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// The number of needs, the length of their descriptions and how far the expiration time can be
+/// are limited by the properties of the DNA
+pub fn validate_dna_properties_limits(
+    call_to_action: &CallToAction,
+    timestamp: &Timestamp,
+    properties: &crate::DnaProperties,
+) -> ValidateCallbackResult {
+    if call_to_action.needs.len() > properties.max_needs as usize {
+        return ValidateCallbackResult::Invalid(format!(
+            "A call to action can't have more than {} needs",
+            properties.max_needs
+        ));
+    }
+    for need in call_to_action.needs.iter() {
+        if need.description.chars().count() > properties.max_description_length as usize {
+            return ValidateCallbackResult::Invalid(format!(
+                "The description of a need can't be longer than {} characters",
+                properties.max_description_length
+            ));
+        }
+    }
+    if let (Some(expiration_time), Some(max_expiration_horizon_seconds)) = (
+        call_to_action.expiration_time,
+        properties.max_expiration_horizon_seconds,
+    ) {
+        let horizon_micros = expiration_time
+            .as_micros()
+            .saturating_sub(timestamp.as_micros());
+        if horizon_micros > (max_expiration_horizon_seconds as i64).saturating_mul(1_000_000) {
+            return ValidateCallbackResult::Invalid(format!(
+                "The expiration time of a call to action can't be more than {} seconds in the future",
+                max_expiration_horizon_seconds
+            ));
+        }
+    }
+    ValidateCallbackResult::Valid
+}
+
+/// Tags must be lowercase and unique so that they always end up in the same index
+pub fn validate_tags(tags: &Vec<String>) -> ValidateCallbackResult {
+    if tags.len() > MAX_TAGS {
+        return ValidateCallbackResult::Invalid(format!(
+            "A call to action can't have more than {} tags",
+            MAX_TAGS
+        ));
+    }
+    let mut seen_tags: BTreeSet<&String> = BTreeSet::new();
+    for tag in tags {
+        if tag.trim().is_empty() {
+            return ValidateCallbackResult::Invalid(String::from("Tags can't be empty"));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return ValidateCallbackResult::Invalid(format!(
+                "Tags can't be longer than {} characters",
+                MAX_TAG_LENGTH
+            ));
+        }
+        if tag.ne(&tag.trim().to_lowercase()) {
+            return ValidateCallbackResult::Invalid(String::from(
+                "Tags must be lowercase and can't start or end with whitespace",
+            ));
+        }
+        if !seen_tags.insert(tag) {
+            return ValidateCallbackResult::Invalid(String::from(
+                "A call to action can't have the same tag twice",
+            ));
+        }
+    }
+    ValidateCallbackResult::Valid
+}
+
+/// The policy must be reachable with the needs of the call to action
+pub fn validate_fulfillment_policy(call_to_action: &CallToAction) -> ValidateCallbackResult {
+    let needs_count = call_to_action.needs.len() as u32;
+    let optional_needs_count = call_to_action
+        .needs
+        .iter()
+        .filter(|need| need.min_necessary == 0)
+        .count() as u32;
+    match &call_to_action.fulfillment_policy {
+        FulfillmentPolicy::AllRequiredNeeds => ValidateCallbackResult::Valid,
+        FulfillmentPolicy::AnyNeeds { count } => {
+            if *count == 0 || *count > needs_count {
+                return ValidateCallbackResult::Invalid(String::from(
+                    "The count of an AnyNeeds policy must be between 1 and the number of needs",
+                ));
+            }
+            ValidateCallbackResult::Valid
+        }
+        FulfillmentPolicy::AllRequiredAndSomeOptionalNeeds { optional_count } => {
+            if *optional_count == 0 || *optional_count > optional_needs_count {
+                return ValidateCallbackResult::Invalid(String::from(
+                    "The optional_count of an AllRequiredAndSomeOptionalNeeds policy must be between 1 and the number of optional needs",
+                ));
+            }
+            ValidateCallbackResult::Valid
+        }
+        FulfillmentPolicy::WeightedThreshold { weights, threshold } => {
+            if weights.len() as u32 != needs_count {
+                return ValidateCallbackResult::Invalid(String::from(
+                    "A WeightedThreshold policy must have one weight for each need",
+                ));
+            }
+            let total_weight = weights.iter().fold(0u64, |acc, w| acc + *w as u64);
+            if *threshold == 0 || *threshold as u64 > total_weight {
+                return ValidateCallbackResult::Invalid(String::from(
+                    "The threshold of a WeightedThreshold policy must be between 1 and the sum of its weights",
+                ));
+            }
+            ValidateCallbackResult::Valid
+        }
+    }
+}
+
+/// There must be enough distinct admins, counting the owner, to reach the approval threshold of every need
+pub fn validate_approval_thresholds(
+    author: &AgentPubKey,
+    call_to_action: &CallToAction,
+) -> ValidateCallbackResult {
+    // The owner might also be listed as an admin, and can only approve once
+    let owner = crate::get_call_to_action_owner(author, call_to_action);
+    let approvers: BTreeSet<&AgentPubKey> = call_to_action
+        .admins
+        .iter()
+        .chain(std::iter::once(&owner))
+        .collect();
+    let max_approvers = approvers.len() as u32;
+    for need in call_to_action.needs.iter() {
+        if need.approval_threshold == 0 || need.approval_threshold > max_approvers {
+            return ValidateCallbackResult::Invalid(format!(
+                "The approval_threshold of a need must be between 1 and the {} admins and owner of the call to action",
+                max_approvers
+            ));
+        }
+    }
+    ValidateCallbackResult::Valid
+}
+
+/// Returns the author of the action that created the call to action, following the updates back
+pub fn get_call_to_action_author(action: &EntryCreationAction) -> ExternResult<AgentPubKey> {
+    let mut action = match action {
+        EntryCreationAction::Create(create) => return Ok(create.author.clone()),
+        EntryCreationAction::Update(update) => update.clone(),
+    };
+    loop {
+        let previous_action = must_get_action(action.original_action_address.clone())?;
+        match previous_action.hashed.content {
+            Action::Create(create) => return Ok(create.author),
+            Action::Update(update) => {
+                action = update;
+            }
+            _ => {
+                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "The original action of a call to action must be a Create or an Update"
+                ))))
+            }
+        }
+    }
+}
+
+/// Returns the author of the call to action and its contents at the version an entry relies on,
+/// which is the call to action as it was created unless the entry references one of its updates
+///
+/// Which update is the latest can't be checked deterministically, so entries reference the version they were created for.
+/// Returns `None` if the referenced version is not a version of the call to action
+pub fn must_get_call_to_action_version(
+    call_to_action_hash: &ActionHash,
+    call_to_action_version_hash: &Option<ActionHash>,
+) -> ExternResult<Option<(AgentPubKey, CallToAction)>> {
+    let version_hash = call_to_action_version_hash
+        .clone()
+        .unwrap_or(call_to_action_hash.clone());
+    let record = must_get_valid_record(version_hash.clone())?;
+    let call_to_action: CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Dependant action must be accompanied by an entry"
+        ))))?;
+    let (original_call_to_action_hash, author) = match record.action() {
+        Action::Create(create) => (version_hash, create.author.clone()),
+        Action::Update(update) => (
+            crate::get_original_call_to_action_hash(update)?,
+            get_call_to_action_author(&EntryCreationAction::Update(update.clone()))?,
+        ),
+        _ => return Ok(None),
+    };
+    if original_call_to_action_hash.ne(call_to_action_hash) {
+        return Ok(None);
+    }
+    Ok(Some((author, call_to_action)))
+}
+
+/// Whether the satisfied needs are enough for the call to action to assemble, according to its fulfillment policy
+pub fn is_fulfillment_policy_met(
+    call_to_action: &CallToAction,
+    satisfied_needs: &BTreeSet<u32>,
+) -> bool {
+    let satisfied_needs: BTreeSet<u32> = satisfied_needs
+        .iter()
+        .filter(|need_index| (**need_index as usize) < call_to_action.needs.len())
+        .cloned()
+        .collect();
+    match &call_to_action.fulfillment_policy {
+        FulfillmentPolicy::AllRequiredNeeds => {
+            are_all_required_needs_satisfied(call_to_action, &satisfied_needs)
+        }
+        FulfillmentPolicy::AnyNeeds { count } => satisfied_needs.len() as u32 >= *count,
+        FulfillmentPolicy::AllRequiredAndSomeOptionalNeeds { optional_count } => {
+            let optional_satisfied_count = satisfied_needs
+                .iter()
+                .filter(|need_index| call_to_action.needs[**need_index as usize].min_necessary == 0)
+                .count() as u32;
+            are_all_required_needs_satisfied(call_to_action, &satisfied_needs)
+                && optional_satisfied_count >= *optional_count
+        }
+        FulfillmentPolicy::WeightedThreshold { weights, threshold } => {
+            let satisfied_weight = satisfied_needs
+                .iter()
+                .filter_map(|need_index| weights.get(*need_index as usize))
+                .fold(0u64, |acc, w| acc + *w as u64);
+            satisfied_weight >= *threshold as u64
+        }
+    }
+}
+
+/// Whether satisfying the need can count towards the fulfillment policy of the call to action
+///
+/// Required needs always count, optional ones only for the policies that take them into account
+pub fn does_fulfillment_policy_count_need(call_to_action: &CallToAction, need_index: u32) -> bool {
+    let Some(need) = call_to_action.needs.get(need_index as usize) else {
+        return false;
+    };
+    if need.min_necessary > 0 {
+        return true;
+    }
+    match &call_to_action.fulfillment_policy {
+        FulfillmentPolicy::AllRequiredNeeds => false,
+        FulfillmentPolicy::AnyNeeds { .. } => true,
+        FulfillmentPolicy::AllRequiredAndSomeOptionalNeeds { .. } => true,
+        FulfillmentPolicy::WeightedThreshold { weights, .. } => weights
+            .get(need_index as usize)
+            .map(|weight| *weight > 0)
+            .unwrap_or(false),
+    }
+}
+
+/// Whether every need with a min_necessary greater than 0 is among the satisfied needs
+pub fn are_all_required_needs_satisfied(
+    call_to_action: &CallToAction,
+    satisfied_needs: &BTreeSet<u32>,
+) -> bool {
+    call_to_action
+        .needs
+        .iter()
+        .enumerate()
+        .all(|(need_index, need)| {
+            need.min_necessary == 0 || satisfied_needs.contains(&(need_index as u32))
+        })
+}
+
+/// Whether the agent owns or administers the call to action, where the author is the owner until they transfer it
+pub fn is_author_or_admin(
+    author: &AgentPubKey,
+    call_to_action: &CallToAction,
+    agent: &AgentPubKey,
+) -> bool {
+    crate::get_call_to_action_owner(author, call_to_action).eq(agent)
+        || call_to_action.admins.contains(agent)
+}
+
 pub fn validate_create_link_call_to_action_to_call_to_actions(
-    _action: CreateLink,
+    action: CreateLink,
     base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    let action_hash =
+    let parent_hash =
         ActionHash::try_from(base_address).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
     let record = must_get_valid_record(action_hash)?;
-    let _call_to_action: crate::CallToAction = record
+    let call_to_action: crate::CallToAction = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
             "Linked action must reference an entry"
         ))))?;
+    if call_to_action
+        .parent_call_to_action_hash
+        .ne(&Some(parent_hash))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A call to action can only be linked from its own parent",
+        )));
+    }
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a child call to action can link it from its parent",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_call_to_action_to_call_to_actions(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "CallToActionToCallToActions links cannot be deleted",
+    )))
+}
+pub fn validate_create_link_agent_to_calls_to_action(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let agent = base_address
+        .into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The base of the link must be an agent"
+        ))))?;
     let action_hash = ActionHash::try_from(target_address)
         .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
     let record = must_get_valid_record(action_hash)?;
@@ -74,9 +592,14 @@ pub fn validate_create_link_call_to_action_to_call_to_actions(
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
             "Linked action must reference an entry"
         ))))?;
+    if record.action().author().ne(&agent) || action.author.ne(&agent) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a call to action can link to it from their agent",
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
-pub fn validate_delete_link_call_to_action_to_call_to_actions(
+pub fn validate_delete_link_agent_to_calls_to_action(
     _action: DeleteLink,
     _original_action: CreateLink,
     _base: AnyLinkableHash,
@@ -84,6 +607,71 @@ pub fn validate_delete_link_call_to_action_to_call_to_actions(
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     Ok(ValidateCallbackResult::Invalid(String::from(
-        "CallToActionToCallToActions links cannot be deleted",
+        "AgentToCallsToAction links cannot be deleted",
     )))
 }
+/// Tag of the AdminToCallsToAction links, with the version of the call to action that made the agent an admin or its owner
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct AdminLinkTag {
+    pub call_to_action_version_hash: ActionHash,
+}
+
+impl TryFrom<AdminLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: AdminLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for AdminLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<AdminLinkTag> {
+        AdminLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+pub fn validate_create_link_admin_to_calls_to_action(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let admin = base_address
+        .into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The base of the link must be an agent"
+        ))))?;
+    let Ok(admin_link_tag) = AdminLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of an AdminToCallsToAction link must be an AdminLinkTag",
+        )));
+    };
+    let Some(call_to_action) = crate::must_get_indexed_call_to_action_version(
+        &action.author,
+        target_address,
+        &admin_link_tag.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can link it from its admins",
+        )));
+    };
+    // The coordinator links the owner with the admins once the call to action is transferred
+    if !call_to_action.admins.contains(&admin) && call_to_action.owner.ne(&Some(admin)) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The referenced version of the call to action doesn't have this agent as an admin or its owner",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_admin_to_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    crate::validate_delete_link_from_index(action, original_action, target)
+}
@@ -6,19 +6,82 @@ pub struct Commitment {
     pub amount: u32,
     pub comment: Option<String>,
     pub need_index: u32,
+    /// The update of the call to action that the commitment relies on, if it's not the call to action as it was created
+    #[serde(default)]
+    pub call_to_action_version_hash: Option<ActionHash>,
 }
+
+/// Tag of the CallToActionToCommitments links, so that progress can be computed without getting every commitment
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct CommitmentLinkTag {
+    pub need_index: u32,
+    pub amount: u32,
+    pub committer: AgentPubKey,
+}
+
+impl CommitmentLinkTag {
+    pub fn new(commitment: &Commitment, committer: AgentPubKey) -> Self {
+        CommitmentLinkTag {
+            need_index: commitment.need_index,
+            amount: commitment.amount,
+            committer,
+        }
+    }
+}
+
+impl TryFrom<CommitmentLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: CommitmentLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for CommitmentLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<CommitmentLinkTag> {
+        CommitmentLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+/// Only rejects a single commitment whose amount exceeds the max_possible of its need:
+/// the sum of all the commitments for the need is only enforced by the coordinator zome,
+/// so concurrent or hand-crafted commitments can still oversubscribe it
 pub fn validate_create_commitment(
-    _action: EntryCreationAction,
+    action: EntryCreationAction,
     commitment: Commitment,
 ) -> ExternResult<ValidateCallbackResult> {
-    let record = must_get_valid_record(commitment.call_to_action_hash.clone())?;
-    let _call_to_action: crate::CallToAction = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Dependant action must be accompanied by an entry"
-        ))))?;
+    let Some((_author, call_to_action)) = crate::must_get_call_to_action_version(
+        &commitment.call_to_action_hash,
+        &commitment.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The call_to_action_version_hash of this commitment is not a version of its call to action",
+        )));
+    };
+
+    if let Some(expiration_time) = call_to_action.expiration_time {
+        if action.timestamp() > &expiration_time {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Can't commit to a call to action that has already expired",
+            )));
+        }
+    }
+
+    let Some(need) = call_to_action.needs.get(commitment.need_index as usize) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The need_index of this commitment does not exist in its call to action",
+        )));
+    };
+
+    if let Some(max_possible) = need.max_possible {
+        if commitment.amount > max_possible {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "The amount of this commitment exceeds the max_possible for its need",
+            )));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_update_commitment(
@@ -41,14 +104,14 @@ pub fn validate_delete_commitment(
     )))
 }
 pub fn validate_create_link_call_to_action_to_commitments(
-    _action: CreateLink,
+    action: CreateLink,
     base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    let action_hash =
+    let call_to_action_hash =
         ActionHash::try_from(base_address).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
-    let record = must_get_valid_record(action_hash)?;
+    let record = must_get_valid_record(call_to_action_hash.clone())?;
     let _call_to_action: crate::CallToAction = record
         .entry()
         .to_app_option()
@@ -59,13 +122,36 @@ pub fn validate_create_link_call_to_action_to_commitments(
     let action_hash = ActionHash::try_from(target_address)
         .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
     let record = must_get_valid_record(action_hash)?;
-    let _commitment: crate::Commitment = record
+    let commitment: crate::Commitment = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
             "Linked action must reference an entry"
         ))))?;
+    if commitment.call_to_action_hash.ne(&call_to_action_hash) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A commitment can only be linked from its own call to action",
+        )));
+    }
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a commitment can link it from its call to action",
+        )));
+    }
+    let Ok(commitment_link_tag) = CommitmentLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of a CallToActionToCommitments link must be a CommitmentLinkTag",
+        )));
+    };
+    if commitment_link_tag.ne(&CommitmentLinkTag::new(
+        &commitment,
+        record.action().author().clone(),
+    )) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of a CallToActionToCommitments link must match its commitment",
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_call_to_action_to_commitments(
@@ -79,3 +165,42 @@ pub fn validate_delete_link_call_to_action_to_commitments(
         "CallToActionToCommitments links cannot be deleted",
     )))
 }
+pub fn validate_create_link_agent_to_commitments(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let agent = base_address
+        .into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The base of the link must be an agent"
+        ))))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let _commitment: crate::Commitment = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if record.action().author().ne(&agent) || action.author.ne(&agent) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a commitment can link to it from their agent",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_agent_to_commitments(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "AgentToCommitments links cannot be deleted",
+    )))
+}
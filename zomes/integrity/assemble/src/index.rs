@@ -0,0 +1,408 @@
+use hdi::hash_path::path::{Component, Path};
+use hdi::prelude::*;
+
+pub fn all_calls_to_action_path() -> Path {
+    Path::from("all_calls_to_action")
+}
+
+pub fn open_calls_to_action_path() -> Path {
+    Path::from("open_calls_to_action")
+}
+
+pub fn closed_calls_to_action_path() -> Path {
+    Path::from("closed_calls_to_action")
+}
+
+pub fn all_calls_to_action_day_path(day: i64) -> Path {
+    day_path(all_calls_to_action_path(), day)
+}
+
+pub fn open_calls_to_action_day_path(day: i64) -> Path {
+    day_path(open_calls_to_action_path(), day)
+}
+
+pub fn closed_calls_to_action_day_path(day: i64) -> Path {
+    day_path(closed_calls_to_action_path(), day)
+}
+
+fn day_path(mut path: Path, day: i64) -> Path {
+    path.append_component(Component::from(day.to_string()));
+    path
+}
+
+pub fn tag_path(tag: &str) -> Path {
+    Path::from(vec![Component::from("tags"), Component::from(tag)])
+}
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+/// Days since the UNIX epoch, which identify the buckets of the expiration index
+pub fn expiration_day(expiration_time: &Timestamp) -> i64 {
+    expiration_time.as_micros().div_euclid(MICROS_PER_DAY)
+}
+
+/// The all, open and closed indexes are split by the day in which the call to action was created,
+/// so that their pages can be read without getting the links to every call to action
+pub fn creation_day(created_at: &Timestamp) -> i64 {
+    created_at.as_micros().div_euclid(MICROS_PER_DAY)
+}
+
+pub fn expiration_day_path(day: i64) -> Path {
+    Path::from(vec![
+        Component::from("expirations"),
+        Component::from(day.to_string()),
+    ])
+}
+
+/// Tag of the ExpirationToCallsToAction links, so that the calls to action of a day
+/// can be filtered by their exact expiration time without getting them
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct ExpirationLinkTag {
+    pub expiration_time: Timestamp,
+    pub call_to_action_version_hash: ActionHash,
+}
+
+impl TryFrom<ExpirationLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: ExpirationLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for ExpirationLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<ExpirationLinkTag> {
+        ExpirationLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+/// Tag of the TagToCallsToAction links, with the version of the call to action that has the tag
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct CallToActionTagLinkTag {
+    pub tag: String,
+    pub call_to_action_version_hash: ActionHash,
+}
+
+impl TryFrom<CallToActionTagLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: CallToActionTagLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for CallToActionTagLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<CallToActionTagLinkTag> {
+        CallToActionTagLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+/// Returns the version of the call to action that an index link references for its contents,
+/// if the author of the link authored that version or is one of its admins or its owner
+pub fn must_get_indexed_call_to_action_version(
+    link_author: &AgentPubKey,
+    target_address: AnyLinkableHash,
+    call_to_action_version_hash: &ActionHash,
+) -> ExternResult<Option<crate::CallToAction>> {
+    let call_to_action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let Some((author, call_to_action)) = crate::must_get_call_to_action_version(
+        &call_to_action_hash,
+        &Some(call_to_action_version_hash.clone()),
+    )?
+    else {
+        return Ok(None);
+    };
+    let version_record = must_get_valid_record(call_to_action_version_hash.clone())?;
+    if version_record.action().author().ne(link_author)
+        && !crate::is_author_or_admin(&author, &call_to_action, link_author)
+    {
+        return Ok(None);
+    }
+    Ok(Some(call_to_action))
+}
+
+/// Index links must go from the path of the index to a call to action
+fn validate_create_link_from_path_to_call_to_action(
+    path: Path,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address.ne(&AnyLinkableHash::from(path.path_entry_hash()?)) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Index links must have the path of their index as their base",
+        )));
+    }
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let _call_to_action: crate::CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The all, open and closed index links must go from the path of the creation day of the call to action
+fn validate_create_link_from_day_path_to_call_to_action(
+    day_path: fn(i64) -> Path,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address.clone())
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let Action::Create(_) = record.action() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Index links must point to the original call to action",
+        )));
+    };
+    validate_create_link_from_path_to_call_to_action(
+        day_path(creation_day(&record.action().timestamp())),
+        base_address,
+        target_address,
+    )
+}
+
+/// Index links can only be removed by their author or by the owner or the admins of the call to action
+pub fn validate_delete_link_from_index(
+    action: DeleteLink,
+    original_action: CreateLink,
+    target: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author.eq(&original_action.author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let action_hash =
+        ActionHash::try_from(target).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let call_to_action: crate::CallToAction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if !crate::is_author_or_admin(record.action().author(), &call_to_action, &action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an index link or the owner and the admins of its call to action can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_all_calls_to_action(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_create_link_from_day_path_to_call_to_action(
+        all_calls_to_action_day_path,
+        base_address,
+        target_address,
+    )
+}
+pub fn validate_delete_link_all_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_from_index(action, original_action, target)
+}
+pub fn validate_create_link_open_calls_to_action(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_create_link_from_day_path_to_call_to_action(
+        open_calls_to_action_day_path,
+        base_address,
+        target_address,
+    )
+}
+pub fn validate_delete_link_open_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_from_index(action, original_action, target)
+}
+pub fn validate_create_link_closed_calls_to_action(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_create_link_from_day_path_to_call_to_action(
+        closed_calls_to_action_day_path,
+        base_address,
+        target_address,
+    )
+}
+pub fn validate_delete_link_closed_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_from_index(action, original_action, target)
+}
+/// Tag of the AllCallsToActionToDays links, so that the days can be sorted without getting their paths
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct DayLinkTag {
+    pub day: i64,
+}
+
+impl TryFrom<DayLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: DayLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for DayLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<DayLinkTag> {
+        DayLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+pub fn validate_create_link_all_calls_to_action_to_days(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(day_link_tag) = DayLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of an AllCallsToActionToDays link must be a DayLinkTag",
+        )));
+    };
+    if base_address.ne(&AnyLinkableHash::from(
+        all_calls_to_action_path().path_entry_hash()?,
+    )) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "AllCallsToActionToDays links must have the path of the all index as their base",
+        )));
+    }
+    if target_address.ne(&AnyLinkableHash::from(
+        all_calls_to_action_day_path(day_link_tag.day).path_entry_hash()?,
+    )) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "AllCallsToActionToDays links must point to the path of the day in their tag",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_all_calls_to_action_to_days(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "AllCallsToActionToDays links cannot be deleted",
+    )))
+}
+pub fn validate_create_link_tag_to_calls_to_action(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(tag_link_tag) = CallToActionTagLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of a TagToCallsToAction link must be a CallToActionTagLinkTag",
+        )));
+    };
+    let Some(call_to_action) = must_get_indexed_call_to_action_version(
+        &action.author,
+        target_address.clone(),
+        &tag_link_tag.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can tag it",
+        )));
+    };
+    if !call_to_action.tags.contains(&tag_link_tag.tag) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The referenced version of the call to action doesn't have this tag",
+        )));
+    }
+    validate_create_link_from_path_to_call_to_action(
+        tag_path(&tag_link_tag.tag),
+        base_address,
+        target_address,
+    )
+}
+pub fn validate_delete_link_tag_to_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_from_index(action, original_action, target)
+}
+pub fn validate_create_link_expiration_to_calls_to_action(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(expiration_link_tag) = ExpirationLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of an ExpirationToCallsToAction link must be an ExpirationLinkTag",
+        )));
+    };
+    let Some(call_to_action) = must_get_indexed_call_to_action_version(
+        &action.author,
+        target_address.clone(),
+        &expiration_link_tag.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can index its expiration time",
+        )));
+    };
+    if call_to_action
+        .expiration_time
+        .ne(&Some(expiration_link_tag.expiration_time))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The referenced version of the call to action doesn't expire at this time",
+        )));
+    }
+    validate_create_link_from_path_to_call_to_action(
+        expiration_day_path(expiration_day(&expiration_link_tag.expiration_time)),
+        base_address,
+        target_address,
+    )
+}
+pub fn validate_delete_link_expiration_to_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_from_index(action, original_action, target)
+}
@@ -7,6 +7,18 @@ pub mod commitment;
 pub use commitment::*;
 pub mod call_to_action;
 pub use call_to_action::*;
+pub mod index;
+pub use index::*;
+pub mod location;
+pub use location::*;
+pub mod approval;
+pub use approval::*;
+pub mod admin;
+pub use admin::*;
+pub mod membrane;
+pub use membrane::*;
+pub mod properties;
+pub use properties::*;
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 #[hdk_entry_defs]
@@ -16,6 +28,8 @@ pub enum EntryTypes {
     Commitment(Commitment),
     Satisfaction(Satisfaction),
     Assembly(Assembly),
+    CommitmentApproval(CommitmentApproval),
+    CommitmentRejection(CommitmentRejection),
 }
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
@@ -26,19 +40,37 @@ pub enum LinkTypes {
     CommitmentToSatisfactions,
     CallToActionToAssemblies,
     SatisfactionToAssemblies,
+    AllCallsToAction,
+    OpenCallsToAction,
+    ClosedCallsToAction,
+    AgentToCallsToAction,
+    AgentToCommitments,
+    AgentToSatisfactions,
+    AgentToAssemblies,
+    TagToCallsToAction,
+    ExpirationToCallsToAction,
+    GeohashToCallsToAction,
+    AdminToCallsToAction,
+    CommitmentToApprovals,
+    CommitmentToRejections,
+    CallToActionToAdminChanges,
+    AllCallsToActionToDays,
 }
 #[hdk_extern]
-pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+pub fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+    validate_membrane_proof(&data.agent_key, &data.membrane_proof)
 }
 pub fn validate_agent_joining(
-    _agent_pub_key: AgentPubKey,
-    _membrane_proof: &Option<MembraneProof>,
+    agent_pub_key: AgentPubKey,
+    membrane_proof: &Option<MembraneProof>,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+    validate_membrane_proof(&agent_pub_key, membrane_proof)
 }
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_assembly_countersigning(&op)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
@@ -55,6 +87,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Assembly(assembly) => {
                     validate_create_assembly(EntryCreationAction::Create(action), assembly)
                 }
+                EntryTypes::CommitmentApproval(commitment_approval) => {
+                    validate_create_commitment_approval(
+                        EntryCreationAction::Create(action),
+                        commitment_approval,
+                    )
+                }
+                EntryTypes::CommitmentRejection(commitment_rejection) => {
+                    validate_create_commitment_rejection(
+                        EntryCreationAction::Create(action),
+                        commitment_rejection,
+                    )
+                }
             },
             OpEntry::UpdateEntry {
                 app_entry, action, ..
@@ -72,6 +116,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Assembly(assembly) => {
                     validate_create_assembly(EntryCreationAction::Update(action), assembly)
                 }
+                EntryTypes::CommitmentApproval(commitment_approval) => {
+                    validate_create_commitment_approval(
+                        EntryCreationAction::Update(action),
+                        commitment_approval,
+                    )
+                }
+                EntryTypes::CommitmentRejection(commitment_rejection) => {
+                    validate_create_commitment_rejection(
+                        EntryCreationAction::Update(action),
+                        commitment_rejection,
+                    )
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -85,6 +141,24 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 (EntryTypes::Assembly(assembly), EntryTypes::Assembly(original_assembly)) => {
                     validate_update_assembly(action, assembly, original_action, original_assembly)
                 }
+                (
+                    EntryTypes::CommitmentApproval(commitment_approval),
+                    EntryTypes::CommitmentApproval(original_commitment_approval),
+                ) => validate_update_commitment_approval(
+                    action,
+                    commitment_approval,
+                    original_action,
+                    original_commitment_approval,
+                ),
+                (
+                    EntryTypes::CommitmentRejection(commitment_rejection),
+                    EntryTypes::CommitmentRejection(original_commitment_rejection),
+                ) => validate_update_commitment_rejection(
+                    action,
+                    commitment_rejection,
+                    original_action,
+                    original_commitment_rejection,
+                ),
                 (
                     EntryTypes::Satisfaction(satisfaction),
                     EntryTypes::Satisfaction(original_satisfaction),
@@ -136,6 +210,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Assembly(assembly) => {
                     validate_delete_assembly(action, original_action, assembly)
                 }
+                EntryTypes::CommitmentApproval(commitment_approval) => {
+                    validate_delete_commitment_approval(
+                        action,
+                        original_action,
+                        commitment_approval,
+                    )
+                }
+                EntryTypes::CommitmentRejection(commitment_rejection) => {
+                    validate_delete_commitment_rejection(
+                        action,
+                        original_action,
+                        commitment_rejection,
+                    )
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -186,12 +274,94 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     tag,
                 )
             }
+            LinkTypes::AllCallsToAction => {
+                validate_create_link_all_calls_to_action(action, base_address, target_address, tag)
+            }
+            LinkTypes::OpenCallsToAction => {
+                validate_create_link_open_calls_to_action(action, base_address, target_address, tag)
+            }
+            LinkTypes::ClosedCallsToAction => validate_create_link_closed_calls_to_action(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToCallsToAction => validate_create_link_agent_to_calls_to_action(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToCommitments => {
+                validate_create_link_agent_to_commitments(action, base_address, target_address, tag)
+            }
+            LinkTypes::AgentToSatisfactions => validate_create_link_agent_to_satisfactions(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToAssemblies => {
+                validate_create_link_agent_to_assemblies(action, base_address, target_address, tag)
+            }
+            LinkTypes::TagToCallsToAction => validate_create_link_tag_to_calls_to_action(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ExpirationToCallsToAction => {
+                validate_create_link_expiration_to_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
+            LinkTypes::GeohashToCallsToAction => validate_create_link_geohash_to_calls_to_action(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AdminToCallsToAction => validate_create_link_admin_to_calls_to_action(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CommitmentToApprovals => validate_create_link_commitment_to_approvals(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CommitmentToRejections => validate_create_link_commitment_to_rejections(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CallToActionToAdminChanges => {
+                validate_create_link_call_to_action_to_admin_changes(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
             LinkTypes::SatisfactionToAssemblies => validate_create_link_satisfaction_to_assemblies(
                 action,
                 base_address,
                 target_address,
                 tag,
             ),
+            LinkTypes::AllCallsToActionToDays => validate_create_link_all_calls_to_action_to_days(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
         },
         FlatOp::RegisterDeleteLink {
             link_type,
@@ -246,6 +416,108 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     tag,
                 )
             }
+            LinkTypes::AllCallsToAction => validate_delete_link_all_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::OpenCallsToAction => validate_delete_link_open_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ClosedCallsToAction => validate_delete_link_closed_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToCallsToAction => validate_delete_link_agent_to_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToCommitments => validate_delete_link_agent_to_commitments(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToSatisfactions => validate_delete_link_agent_to_satisfactions(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AgentToAssemblies => validate_delete_link_agent_to_assemblies(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::TagToCallsToAction => validate_delete_link_tag_to_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::ExpirationToCallsToAction => {
+                validate_delete_link_expiration_to_calls_to_action(
+                    action,
+                    original_action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
+            LinkTypes::GeohashToCallsToAction => validate_delete_link_geohash_to_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::AdminToCallsToAction => validate_delete_link_admin_to_calls_to_action(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CommitmentToApprovals => validate_delete_link_commitment_to_approvals(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CommitmentToRejections => validate_delete_link_commitment_to_rejections(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::CallToActionToAdminChanges => {
+                validate_delete_link_call_to_action_to_admin_changes(
+                    action,
+                    original_action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
             LinkTypes::SatisfactionToAssemblies => validate_delete_link_satisfaction_to_assemblies(
                 action,
                 original_action,
@@ -253,6 +525,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 target_address,
                 tag,
             ),
+            LinkTypes::AllCallsToActionToDays => validate_delete_link_all_calls_to_action_to_days(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
         },
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
@@ -269,6 +548,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Assembly(assembly) => {
                     validate_create_assembly(EntryCreationAction::Create(action), assembly)
                 }
+                EntryTypes::CommitmentApproval(commitment_approval) => {
+                    validate_create_commitment_approval(
+                        EntryCreationAction::Create(action),
+                        commitment_approval,
+                    )
+                }
+                EntryTypes::CommitmentRejection(commitment_rejection) => {
+                    validate_create_commitment_rejection(
+                        EntryCreationAction::Create(action),
+                        commitment_rejection,
+                    )
+                }
             },
             OpRecord::UpdateEntry {
                 original_action_hash,
@@ -413,6 +704,71 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             Ok(result)
                         }
                     }
+                    EntryTypes::CommitmentApproval(commitment_approval) => {
+                        let result = validate_create_commitment_approval(
+                            EntryCreationAction::Update(action.clone()),
+                            commitment_approval.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_commitment_approval: Option<CommitmentApproval> =
+                                original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                            let original_commitment_approval = match original_commitment_approval {
+                                Some(commitment_approval) => commitment_approval,
+                                None => {
+                                    return Ok(
+                                            ValidateCallbackResult::Invalid(
+                                                "The updated entry type must be the same as the original entry type"
+                                                    .to_string(),
+                                            ),
+                                        );
+                                }
+                            };
+                            validate_update_commitment_approval(
+                                action,
+                                commitment_approval,
+                                original_action,
+                                original_commitment_approval,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::CommitmentRejection(commitment_rejection) => {
+                        let result = validate_create_commitment_rejection(
+                            EntryCreationAction::Update(action.clone()),
+                            commitment_rejection.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_commitment_rejection: Option<CommitmentRejection> =
+                                original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                            let original_commitment_rejection = match original_commitment_rejection
+                            {
+                                Some(commitment_rejection) => commitment_rejection,
+                                None => {
+                                    return Ok(
+                                            ValidateCallbackResult::Invalid(
+                                                "The updated entry type must be the same as the original entry type"
+                                                    .to_string(),
+                                            ),
+                                        );
+                                }
+                            };
+                            validate_update_commitment_rejection(
+                                action,
+                                commitment_rejection,
+                                original_action,
+                                original_commitment_rejection,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
                 }
             }
             OpRecord::DeleteEntry {
@@ -480,6 +836,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryTypes::Assembly(original_assembly) => {
                         validate_delete_assembly(action, original_action, original_assembly)
                     }
+                    EntryTypes::CommitmentApproval(original_commitment_approval) => {
+                        validate_delete_commitment_approval(
+                            action,
+                            original_action,
+                            original_commitment_approval,
+                        )
+                    }
+                    EntryTypes::CommitmentRejection(original_commitment_rejection) => {
+                        validate_delete_commitment_rejection(
+                            action,
+                            original_action,
+                            original_commitment_rejection,
+                        )
+                    }
                 }
             }
             OpRecord::CreateLink {
@@ -529,6 +899,96 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         tag,
                     )
                 }
+                LinkTypes::AllCallsToAction => validate_create_link_all_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::OpenCallsToAction => validate_create_link_open_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::ClosedCallsToAction => validate_create_link_closed_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::AgentToCallsToAction => validate_create_link_agent_to_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::AgentToCommitments => validate_create_link_agent_to_commitments(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::AgentToSatisfactions => validate_create_link_agent_to_satisfactions(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::AgentToAssemblies => validate_create_link_agent_to_assemblies(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::TagToCallsToAction => validate_create_link_tag_to_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::ExpirationToCallsToAction => {
+                    validate_create_link_expiration_to_calls_to_action(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
+                LinkTypes::GeohashToCallsToAction => {
+                    validate_create_link_geohash_to_calls_to_action(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
+                LinkTypes::AdminToCallsToAction => validate_create_link_admin_to_calls_to_action(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::CommitmentToApprovals => validate_create_link_commitment_to_approvals(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::CommitmentToRejections => validate_create_link_commitment_to_rejections(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::CallToActionToAdminChanges => {
+                    validate_create_link_call_to_action_to_admin_changes(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
                 LinkTypes::SatisfactionToAssemblies => {
                     validate_create_link_satisfaction_to_assemblies(
                         action,
@@ -537,6 +997,14 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         tag,
                     )
                 }
+                LinkTypes::AllCallsToActionToDays => {
+                    validate_create_link_all_calls_to_action_to_days(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
             },
             OpRecord::DeleteLink {
                 original_action_hash,
@@ -607,6 +1075,118 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             create_link.tag,
                         )
                     }
+                    LinkTypes::AllCallsToAction => validate_delete_link_all_calls_to_action(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::OpenCallsToAction => validate_delete_link_open_calls_to_action(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::ClosedCallsToAction => validate_delete_link_closed_calls_to_action(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::AgentToCallsToAction => {
+                        validate_delete_link_agent_to_calls_to_action(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::AgentToCommitments => validate_delete_link_agent_to_commitments(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::AgentToSatisfactions => validate_delete_link_agent_to_satisfactions(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::AgentToAssemblies => validate_delete_link_agent_to_assemblies(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::TagToCallsToAction => validate_delete_link_tag_to_calls_to_action(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::ExpirationToCallsToAction => {
+                        validate_delete_link_expiration_to_calls_to_action(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::GeohashToCallsToAction => {
+                        validate_delete_link_geohash_to_calls_to_action(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::AdminToCallsToAction => {
+                        validate_delete_link_admin_to_calls_to_action(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::CommitmentToApprovals => {
+                        validate_delete_link_commitment_to_approvals(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::CommitmentToRejections => {
+                        validate_delete_link_commitment_to_rejections(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::CallToActionToAdminChanges => {
+                        validate_delete_link_call_to_action_to_admin_changes(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
                     LinkTypes::SatisfactionToAssemblies => {
                         validate_delete_link_satisfaction_to_assemblies(
                             action,
@@ -616,6 +1196,15 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             create_link.tag,
                         )
                     }
+                    LinkTypes::AllCallsToActionToDays => {
+                        validate_delete_link_all_calls_to_action_to_days(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
                 }
             }
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
@@ -0,0 +1,163 @@
+use hdi::hash_path::path::{Component, Path};
+use hdi::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub place_name: String,
+}
+
+pub const MAX_PLACE_NAME_LENGTH: usize = 256;
+
+/// Calls to action are indexed under every geohash prefix up to this length (~5km cells)
+pub const GEOHASH_PRECISION: usize = 5;
+
+const GEOHASH_BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+pub fn validate_location(location: &Location) -> ValidateCallbackResult {
+    if !location.latitude.is_finite() || location.latitude < -90.0 || location.latitude > 90.0 {
+        return ValidateCallbackResult::Invalid(String::from(
+            "The latitude of a location must be between -90 and 90",
+        ));
+    }
+    if !location.longitude.is_finite() || location.longitude < -180.0 || location.longitude > 180.0
+    {
+        return ValidateCallbackResult::Invalid(String::from(
+            "The longitude of a location must be between -180 and 180",
+        ));
+    }
+    if location.place_name.chars().count() > MAX_PLACE_NAME_LENGTH {
+        return ValidateCallbackResult::Invalid(format!(
+            "The place name of a location can't be longer than {} characters",
+            MAX_PLACE_NAME_LENGTH
+        ));
+    }
+    ValidateCallbackResult::Valid
+}
+
+/// Encodes the coordinates as a geohash of the given length
+pub fn geohash(latitude: f64, longitude: f64, precision: usize) -> String {
+    let mut latitude_range = (-90.0, 90.0);
+    let mut longitude_range = (-180.0, 180.0);
+    let mut hash = String::with_capacity(precision);
+    let mut bits: usize = 0;
+    let mut bits_count = 0;
+    let mut is_longitude_bit = true;
+
+    while hash.len() < precision {
+        let (value, range) = match is_longitude_bit {
+            true => (longitude, &mut longitude_range),
+            false => (latitude, &mut latitude_range),
+        };
+        let middle = (range.0 + range.1) / 2.0;
+        if value >= middle {
+            bits = (bits << 1) | 1;
+            range.0 = middle;
+        } else {
+            bits <<= 1;
+            range.1 = middle;
+        }
+        is_longitude_bit = !is_longitude_bit;
+        bits_count += 1;
+
+        if bits_count == 5 {
+            hash.push(GEOHASH_BASE32[bits] as char);
+            bits = 0;
+            bits_count = 0;
+        }
+    }
+
+    hash
+}
+
+pub fn geohash_path(geohash: &str) -> Path {
+    Path::from(vec![Component::from("geohashes"), Component::from(geohash)])
+}
+
+/// Tag of the GeohashToCallsToAction links, so that the calls to action in a cell
+/// can be filtered by their exact coordinates without getting them
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct LocationLinkTag {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub call_to_action_version_hash: ActionHash,
+}
+
+impl TryFrom<LocationLinkTag> for LinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LocationLinkTag) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+}
+
+impl TryFrom<LinkTag> for LocationLinkTag {
+    type Error = WasmError;
+    fn try_from(tag: LinkTag) -> ExternResult<LocationLinkTag> {
+        LocationLinkTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.0)))
+            .map_err(|e| wasm_error!(e))
+    }
+}
+
+pub fn validate_create_link_geohash_to_calls_to_action(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(location_link_tag) = LocationLinkTag::try_from(tag) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The tag of a GeohashToCallsToAction link must be a LocationLinkTag",
+        )));
+    };
+    let Some(call_to_action) = crate::must_get_indexed_call_to_action_version(
+        &action.author,
+        target_address.clone(),
+        &location_link_tag.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner or the admins of a call to action can index its location",
+        )));
+    };
+    let is_at_location = match &call_to_action.location {
+        Some(location) => {
+            location.latitude == location_link_tag.latitude
+                && location.longitude == location_link_tag.longitude
+        }
+        None => false,
+    };
+    if !is_at_location {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The referenced version of the call to action isn't at this location",
+        )));
+    }
+    let hash = geohash(
+        location_link_tag.latitude,
+        location_link_tag.longitude,
+        GEOHASH_PRECISION,
+    );
+    let mut is_geohash_prefix = false;
+    for precision in 1..=GEOHASH_PRECISION {
+        let path_hash = geohash_path(&hash[..precision]).path_entry_hash()?;
+        if base_address.eq(&AnyLinkableHash::from(path_hash)) {
+            is_geohash_prefix = true;
+        }
+    }
+    if !is_geohash_prefix {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "GeohashToCallsToAction links must have the path of a prefix of the geohash of their location as their base",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_geohash_to_calls_to_action(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    crate::validate_delete_link_from_index(action, original_action, target)
+}
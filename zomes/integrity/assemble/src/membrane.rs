@@ -0,0 +1,92 @@
+use hdi::prelude::*;
+
+use crate::DnaProperties;
+
+/// An existing member inviting a new agent to join this DNA
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Invitation {
+    pub dna_hash: DnaHash,
+    pub invitee: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SignedInvitation {
+    pub invitation: Invitation,
+    pub inviter: AgentPubKey,
+    pub signature: Signature,
+}
+
+/// The chain of invitations from the progenitor to the joining agent,
+/// so that every invitation can be checked without depending on the DHT
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct AssembleMembraneProof {
+    pub invitations: Vec<SignedInvitation>,
+}
+
+impl TryFrom<MembraneProof> for AssembleMembraneProof {
+    type Error = WasmError;
+    fn try_from(membrane_proof: MembraneProof) -> ExternResult<AssembleMembraneProof> {
+        AssembleMembraneProof::try_from(membrane_proof.as_ref().clone())
+            .map_err(|err| wasm_error!(err))
+    }
+}
+
+/// DNAs without a progenitor are open to every agent, otherwise every agent but the progenitor
+/// needs a chain of invitations that starts with the progenitor and ends with them
+pub fn validate_membrane_proof(
+    agent_pub_key: &AgentPubKey,
+    membrane_proof: &Option<MembraneProof>,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(progenitor) = DnaProperties::get()?.progenitor() else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    if progenitor.eq(agent_pub_key) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let Some(membrane_proof) = membrane_proof.clone() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Joining this DNA requires an invitation from one of its members",
+        )));
+    };
+    let Ok(membrane_proof) = AssembleMembraneProof::try_from(membrane_proof) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Malformed membrane proof",
+        )));
+    };
+    if membrane_proof.invitations.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Joining this DNA requires an invitation from one of its members",
+        )));
+    }
+
+    let dna_hash = dna_info()?.hash;
+    let mut member = progenitor;
+    for signed_invitation in membrane_proof.invitations {
+        if signed_invitation.inviter.ne(&member) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Every invitation must be signed by the progenitor or by an agent that was invited before",
+            )));
+        }
+        if signed_invitation.invitation.dna_hash.ne(&dna_hash) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "The invitation is for another DNA",
+            )));
+        }
+        if !verify_signature(
+            signed_invitation.inviter,
+            signed_invitation.signature,
+            &signed_invitation.invitation,
+        )? {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "The signature of the invitation is not valid",
+            )));
+        }
+        member = signed_invitation.invitation.invitee;
+    }
+    if member.ne(agent_pub_key) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The invitation is for another agent",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,75 @@
+use hdi::prelude::*;
+
+/// The properties of this DNA, set in the `properties` of its `dna.yaml` or as a modifier when installing it,
+/// so that the same DNA can serve communities with different policies
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct DnaProperties {
+    /// The agent that founded the community, which only lets in the agents invited by its existing members
+    #[serde(default)]
+    pub progenitor: Option<AgentPubKeyB64>,
+    #[serde(default = "default_max_needs")]
+    pub max_needs: u32,
+    /// Maximum number of characters in the description of a need
+    #[serde(default = "default_max_description_length")]
+    pub max_description_length: u32,
+    /// How far in the future the expiration time of a call to action can be, if limited
+    #[serde(default)]
+    pub max_expiration_horizon_seconds: Option<u64>,
+    /// When disabled, only the progenitor can create calls to action
+    #[serde(default = "default_anyone_can_create_calls_to_action")]
+    pub anyone_can_create_calls_to_action: bool,
+    /// The zome that the coordinator and the UI bridge to for cancelling commitments
+    #[serde(default = "default_cancellations_zome_name")]
+    pub cancellations_zome_name: String,
+    /// The zome that the UI reads the profiles of the agents from
+    #[serde(default = "default_profiles_zome_name")]
+    pub profiles_zome_name: String,
+}
+
+fn default_max_needs() -> u32 {
+    32
+}
+
+fn default_max_description_length() -> u32 {
+    1024
+}
+
+fn default_anyone_can_create_calls_to_action() -> bool {
+    true
+}
+
+fn default_cancellations_zome_name() -> String {
+    String::from("cancellations")
+}
+
+fn default_profiles_zome_name() -> String {
+    String::from("profiles")
+}
+
+impl Default for DnaProperties {
+    fn default() -> Self {
+        DnaProperties {
+            progenitor: None,
+            max_needs: default_max_needs(),
+            max_description_length: default_max_description_length(),
+            max_expiration_horizon_seconds: None,
+            anyone_can_create_calls_to_action: default_anyone_can_create_calls_to_action(),
+            cancellations_zome_name: default_cancellations_zome_name(),
+            profiles_zome_name: default_profiles_zome_name(),
+        }
+    }
+}
+
+impl DnaProperties {
+    /// DNAs without properties get the default ones
+    pub fn get() -> ExternResult<DnaProperties> {
+        let properties = dna_info()?.modifiers.properties;
+        let properties: Option<DnaProperties> =
+            decode(properties.bytes()).map_err(|err| wasm_error!(err))?;
+        Ok(properties.unwrap_or_default())
+    }
+
+    pub fn progenitor(&self) -> Option<AgentPubKey> {
+        self.progenitor.clone().map(AgentPubKey::from)
+    }
+}
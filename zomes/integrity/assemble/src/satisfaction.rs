@@ -1,43 +1,125 @@
 use hdi::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct Satisfaction {
     pub call_to_action_hash: ActionHash,
     pub need_index: u32,
     pub commitments_hashes: Vec<ActionHash>,
+    #[serde(default)]
+    pub approvals_hashes: Vec<ActionHash>,
+    /// The update of the call to action that the satisfaction relies on, if it's not the call to action as it was created
+    #[serde(default)]
+    pub call_to_action_version_hash: Option<ActionHash>,
 }
 pub fn validate_create_satisfaction(
     action: EntryCreationAction,
     satisfaction: Satisfaction,
 ) -> ExternResult<ValidateCallbackResult> {
-    let record = must_get_valid_record(satisfaction.call_to_action_hash.clone())?;
-    let call_to_action: crate::CallToAction = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Dependant action must be accompanied by an entry"
-        ))))?;
+    let Some((author, call_to_action)) = crate::must_get_call_to_action_version(
+        &satisfaction.call_to_action_hash,
+        &satisfaction.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The call_to_action_version_hash of this satisfaction is not a version of its call to action",
+        )));
+    };
 
-    if call_to_action.needs[satisfaction.need_index as usize].requires_admin_approval {
-        let mut admins = call_to_action.admins.clone();
-        admins.push(record.action().author().clone());
-        if !admins.contains(action.author()) {
+    if let Some(expiration_time) = call_to_action.expiration_time {
+        if action.timestamp() > &expiration_time {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Can't satisfy a need of a call to action that has already expired",
+            )));
+        }
+    }
+
+    let Some(need) = call_to_action.needs.get(satisfaction.need_index as usize) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The need_index of this satisfaction does not exist in its call to action",
+        )));
+    };
+
+    if need.requires_admin_approval {
+        if !crate::is_author_or_admin(&author, &call_to_action, action.author()) {
             return Ok(ValidateCallbackResult::Invalid(String::from(
                 "Only the admins for this call to action can satisfy its needs",
             )));
         }
     }
 
+    let mut commitments_hashes: HashSet<ActionHash> = HashSet::new();
+    let mut amount_contributed: u32 = 0;
     for action_hash in satisfaction.commitments_hashes.clone() {
+        if !commitments_hashes.insert(action_hash.clone()) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "A satisfaction can't reference the same commitment twice",
+            )));
+        }
         let record = must_get_valid_record(action_hash)?;
-        let _commitment: crate::Commitment = record
+        let commitment: crate::Commitment = record
             .entry()
             .to_app_option()
             .map_err(|e| wasm_error!(e))?
             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
                 "Dependant action must be accompanied by an entry"
             ))))?;
+        if commitment
+            .call_to_action_hash
+            .ne(&satisfaction.call_to_action_hash)
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "All the commitments of a satisfaction must be for its call to action",
+            )));
+        }
+        if commitment.need_index != satisfaction.need_index {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "All the commitments of a satisfaction must be for its need",
+            )));
+        }
+        amount_contributed = amount_contributed.saturating_add(commitment.amount);
+    }
+
+    if amount_contributed < need.amount_to_satisfy() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The commitments of a satisfaction must add up to the min_necessary of its need, or to at least 1 for optional needs",
+        )));
+    }
+
+    if need.requires_admin_approval {
+        // Approvals can only be authored by the author or the admins of the call to action
+        let mut approvers: BTreeMap<ActionHash, BTreeSet<AgentPubKey>> = BTreeMap::new();
+        for action_hash in satisfaction.approvals_hashes.clone() {
+            let record = must_get_valid_record(action_hash)?;
+            let commitment_approval: crate::CommitmentApproval = record
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "Dependant action must be accompanied by an entry"
+                ))))?;
+            if !commitments_hashes.contains(&commitment_approval.commitment_hash) {
+                return Ok(ValidateCallbackResult::Invalid(String::from(
+                    "All the approvals of a satisfaction must be for its commitments",
+                )));
+            }
+            approvers
+                .entry(commitment_approval.commitment_hash)
+                .or_default()
+                .insert(record.action().author().clone());
+        }
+        for commitment_hash in commitments_hashes {
+            let approvers_count = approvers
+                .get(&commitment_hash)
+                .map(|approvers| approvers.len() as u32)
+                .unwrap_or(0);
+            if approvers_count < need.approval_threshold {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Every commitment of this satisfaction must be approved by at least {} admins",
+                    need.approval_threshold
+                )));
+            }
+        }
     }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -50,10 +132,66 @@ pub fn validate_update_satisfaction(
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_satisfaction(
-    _action: Delete,
-    _original_action: EntryCreationAction,
-    _original_satisfaction: Satisfaction,
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_satisfaction: Satisfaction,
 ) -> ExternResult<ValidateCallbackResult> {
+    if !can_delete_satisfaction(
+        original_action.author(),
+        &original_satisfaction,
+        &action.author,
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents involved in a satisfaction or the admins of its call to action can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// Satisfactions can be deleted by the agents involved in them, so that committers can delete the ones that rely
+/// on the commitments they cancel, and by the owner and the admins of the version of the call to action they rely on
+///
+/// Cancellations live in their own zome, so whether a commitment was actually cancelled can't be checked here
+pub fn can_delete_satisfaction(
+    satisfaction_author: &AgentPubKey,
+    satisfaction: &Satisfaction,
+    agent: &AgentPubKey,
+) -> ExternResult<bool> {
+    if get_satisfaction_agents(satisfaction_author, satisfaction)?.contains(agent) {
+        return Ok(true);
+    }
+    let Some((author, call_to_action)) = crate::must_get_call_to_action_version(
+        &satisfaction.call_to_action_hash,
+        &satisfaction.call_to_action_version_hash,
+    )?
+    else {
+        return Ok(false);
+    };
+    Ok(crate::is_author_or_admin(&author, &call_to_action, agent))
+}
+/// The links to a satisfaction can be deleted by their author or by the agents that can delete the satisfaction
+fn validate_delete_link_to_satisfaction(
+    action: &DeleteLink,
+    original_action: &CreateLink,
+    target: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action.author.eq(&action.author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let action_hash =
+        ActionHash::try_from(target).map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let satisfaction: crate::Satisfaction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if !can_delete_satisfaction(record.action().author(), &satisfaction, &action.author)? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents that can delete a satisfaction can delete the links to it",
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_create_link_call_to_action_to_satisfactions(
@@ -85,13 +223,13 @@ pub fn validate_create_link_call_to_action_to_satisfactions(
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_call_to_action_to_satisfactions(
-    _action: DeleteLink,
-    _original_action: CreateLink,
+    action: DeleteLink,
+    original_action: CreateLink,
     _base: AnyLinkableHash,
-    _target: AnyLinkableHash,
+    target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+    validate_delete_link_to_satisfaction(&action, &original_action, target)
 }
 pub fn validate_create_link_commitment_to_satisfactions(
     _action: CreateLink,
@@ -122,11 +260,72 @@ pub fn validate_create_link_commitment_to_satisfactions(
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_commitment_to_satisfactions(
-    _action: DeleteLink,
-    _original_action: CreateLink,
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    validate_delete_link_to_satisfaction(&action, &original_action, target)
+}
+
+/// Returns the author of the satisfaction and the authors of its commitments
+pub fn get_satisfaction_agents(
+    author: &AgentPubKey,
+    satisfaction: &Satisfaction,
+) -> ExternResult<BTreeSet<AgentPubKey>> {
+    let mut agents: BTreeSet<AgentPubKey> = BTreeSet::new();
+    agents.insert(author.clone());
+    for commitment_hash in satisfaction.commitments_hashes.clone() {
+        let record = must_get_valid_record(commitment_hash)?;
+        agents.insert(record.action().author().clone());
+    }
+    Ok(agents)
+}
+pub fn validate_create_link_agent_to_satisfactions(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let agent = base_address
+        .into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The base of the link must be an agent"
+        ))))?;
+    let action_hash = ActionHash::try_from(target_address)
+        .map_err(|err| wasm_error!(WasmErrorInner::from(err)))?;
+    let record = must_get_valid_record(action_hash)?;
+    let satisfaction: crate::Satisfaction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a satisfaction can link to it from the agents involved in it",
+        )));
+    }
+    if !get_satisfaction_agents(record.action().author(), &satisfaction)?.contains(&agent) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Can't link to a satisfaction from an agent that is not involved in it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_agent_to_satisfactions(
+    action: DeleteLink,
+    original_action: CreateLink,
     _base: AnyLinkableHash,
     _target: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
+    if action.author.ne(&original_action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an AgentToSatisfactions link can delete it",
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }